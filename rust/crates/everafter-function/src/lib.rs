@@ -1,4 +1,3 @@
-#![feature(proc_macro_diagnostic)]
 #![allow(dead_code)]
 
 mod args;
@@ -10,12 +10,10 @@ impl Parse for MandatoryReturn {
         let ret: ReturnType = input.parse()?;
 
         match ret {
-            ReturnType::Default => {
-                return Err(syn::Error::new(
-                    input.span(),
-                    "everafter functions must have a return value",
-                ))
-            }
+            ReturnType::Default => Err(syn::Error::new(
+                input.span(),
+                "everafter functions must have a return value",
+            )),
             ReturnType::Type(arrow, ty) => Ok(MandatoryReturn { arrow, ty }),
         }
     }
@@ -0,0 +1,99 @@
+use std::fmt::{self, Display};
+
+use crate::{
+    timeline::{DynId, IdKind, Revision},
+    Key,
+};
+
+/// An error produced by a fallible (`try_`) timeline API. The infallible APIs panic with the same
+/// message instead.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Error {
+    /// No inputs of this type were ever added to the timeline.
+    UnknownType { type_name: &'static str },
+    /// The id was never handed out by this timeline.
+    UnknownId { id: DynId },
+    /// The id was used as a different kind of id than the one it was created as.
+    KindMismatch { expected: IdKind, actual: IdKind },
+    /// A type-erased id was used as an id of a different type than the one it was created as.
+    TypeMismatch {
+        expected: &'static str,
+        actual: &'static str,
+    },
+    /// The input was deleted.
+    Removed { id: DynId },
+    /// A derived computation (transitively) read its own value.
+    Cycle { path: Vec<DynId> },
+    /// The input changed since `revision`, and doesn't remember the value it had back then.
+    HistoryUnavailable { id: DynId, revision: Revision },
+    /// The value of the cell persisted as `name` couldn't be saved in a snapshot, or restored from
+    /// one.
+    Snapshot { name: String, message: String },
+    /// Another cell was already persisted under this name.
+    NameTaken { name: String },
+    /// An item was added to a list that already had an item with the same key.
+    DuplicateKey { key: Key },
+    /// A list didn't have an item with this key.
+    MissingKey { key: Key },
+    /// An item was placed past the end of a list of `len` items.
+    OutOfBounds { index: usize, len: usize },
+}
+
+impl Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::UnknownType { type_name } => {
+                write!(f, "no inputs of type {} were registered", type_name)
+            }
+            Error::UnknownId { id } => write!(f, "{} didn't exist", id),
+            Error::KindMismatch { expected, actual } => write!(
+                f,
+                "attempted to downcast a {:?} into a {:?}",
+                actual, expected
+            ),
+            Error::TypeMismatch { expected, actual } => {
+                write!(f, "Can't downcast DynId of {} to {}", actual, expected)
+            }
+            Error::Removed { id } => write!(f, "{} was removed", id),
+            Error::HistoryUnavailable { id, revision } => {
+                write!(f, "{} doesn't remember its value at {}", id, revision)
+            }
+            Error::Snapshot { name, message } => {
+                write!(
+                    f,
+                    "couldn't snapshot the cell named {:?}: {}",
+                    name, message
+                )
+            }
+            Error::NameTaken { name } => {
+                write!(f, "a cell was already persisted as {:?}", name)
+            }
+            Error::DuplicateKey { key } => write!(
+                f,
+                "attempted to insert {:?} into a list that already had it",
+                key
+            ),
+            Error::MissingKey { key } => write!(f, "{:?} wasn't in the list", key),
+            Error::OutOfBounds { index, len } => write!(
+                f,
+                "index {} is out of bounds for a list of {} items",
+                index, len
+            ),
+            Error::Cycle { path } => {
+                write!(f, "cycle detected while computing derived values: ")?;
+
+                for (index, id) in path.iter().enumerate() {
+                    if index > 0 {
+                        write!(f, " -> ")?;
+                    }
+
+                    write!(f, "{}", id)?;
+                }
+
+                Ok(())
+            }
+        }
+    }
+}
+
+impl std::error::Error for Error {}
@@ -1,5 +1,5 @@
 use derive_new::new;
-use std::{fmt::Debug, sync::Arc};
+use std::{collections::VecDeque, fmt::Debug, sync::Arc};
 
 use crate::timeline::revision::{AtomicRevision, Revision};
 
@@ -19,15 +19,40 @@ impl Tag {
 #[derive(Debug, new)]
 pub(crate) struct ReactiveCell<T>
 where
-    T: Debug + Clone + 'static,
+    T: 'static,
 {
     value: T,
     tag: Arc<Tag>,
+    /// `None` unless the cell was set up to remember its past values.
+    #[new(default)]
+    history: Option<History<T>>,
+}
+
+/// The values that a cell held before its current value, oldest first, along with the revisions
+/// at which they were written.
+#[derive(Debug)]
+struct History<T> {
+    entries: VecDeque<(T, Revision)>,
+    /// How many past values to keep.
+    depth: usize,
+    /// Mutating a cell in place has to keep a copy of the value from before the mutation.
+    clone: fn(&T) -> T,
+}
+
+impl<T> History<T> {
+    /// Remember a value that was replaced, forgetting the oldest value if the history is full.
+    fn push(&mut self, value: T, revision: Revision) {
+        if self.entries.len() == self.depth {
+            self.entries.pop_front();
+        }
+
+        self.entries.push_back((value, revision));
+    }
 }
 
 impl<T> Reactive for ReactiveCell<T>
 where
-    T: Debug + Clone + 'static,
+    T: 'static,
 {
     fn get_tag(&self) -> ReactiveTag {
         ReactiveTag::Tag(self.tag.clone())
@@ -36,21 +61,141 @@ where
 
 impl<T> ReactiveCell<T>
 where
-    T: Debug + Clone,
+    T: 'static,
 {
-    pub(crate) fn read(&self) -> T {
-        self.value.clone()
+    /// A cell that remembers up to `depth` of the values that it held before its current value.
+    /// A depth of 0 doesn't remember anything, just like a plain cell.
+    pub(crate) fn with_history(value: T, tag: Arc<Tag>, depth: usize) -> ReactiveCell<T>
+    where
+        T: Clone,
+    {
+        let history = if depth == 0 {
+            None
+        } else {
+            Some(History {
+                entries: VecDeque::new(),
+                depth,
+                clone: Clone::clone,
+            })
+        };
+
+        ReactiveCell {
+            value,
+            tag,
+            history,
+        }
+    }
+
+    /// Like `read`, but borrows the value instead of cloning it.
+    pub(crate) fn borrow(&self) -> &T {
+        &self.value
     }
 
     /**
-     * Update must only be called outside of an archive step.
+     * Update must only be called outside of an archive step. Returns the previous value and
+     * revision. A cell with a history also keeps a copy of them.
      */
-    pub(crate) fn update(&mut self, value: T, revision: Revision) {
-        self.value = value;
+    pub(crate) fn update(&mut self, value: T, revision: Revision) -> (T, Revision) {
+        let previous = std::mem::replace(&mut self.value, value);
+        let previous_revision = self.tag.revision.get();
+        self.tag.revision.update(revision);
+
+        if let Some(history) = &mut self.history {
+            history.push((history.clone)(&previous), previous_revision);
+        }
+
+        (previous, previous_revision)
+    }
+
+    /**
+     * Like `update`, but mutates the existing value in place.
+     */
+    pub(crate) fn update_with<U>(&mut self, f: impl FnOnce(&mut T) -> U, revision: Revision) -> U {
+        if let Some(history) = &mut self.history {
+            let previous = (history.clone)(&self.value);
+            history.push(previous, self.tag.revision.get());
+        }
+
+        let result = f(&mut self.value);
         self.tag.revision.update(revision);
+        result
+    }
+
+    /**
+     * Like `update_with`, but `f` can fail. When it fails, it must leave the value alone, and the
+     * revision and history of the cell are left alone as well.
+     */
+    pub(crate) fn try_update_with<U, E>(
+        &mut self,
+        f: impl FnOnce(&mut T) -> Result<U, E>,
+        revision: Revision,
+    ) -> Result<U, E> {
+        let previous = self
+            .history
+            .as_ref()
+            .map(|history| (history.clone)(&self.value));
+
+        let result = f(&mut self.value)?;
+
+        if let (Some(history), Some(previous)) = (&mut self.history, previous) {
+            history.push(previous, self.tag.revision.get());
+        }
+
+        self.tag.revision.update(revision);
+        Ok(result)
     }
 
     pub(crate) fn revision(&self) -> Revision {
         self.tag.revision.get()
     }
+
+    /// Undo the last update, by putting back the value and revision that it replaced. The update
+    /// pushed that value onto the history, unless the history has since forgotten it along with
+    /// every older value, so the newest entry is dropped if there is one.
+    pub(crate) fn restore(&mut self, value: T, revision: Revision) {
+        if let Some(history) = &mut self.history {
+            history.entries.pop_back();
+        }
+
+        self.value = value;
+        self.tag.revision.update(revision);
+    }
+
+    /// The value that the cell held at `revision`, if the cell still remembers it.
+    pub(crate) fn borrow_at(&self, revision: Revision) -> Option<&T> {
+        if self.revision() <= revision {
+            return Some(&self.value);
+        }
+
+        let history = self.history.as_ref()?;
+        history
+            .entries
+            .iter()
+            .rev()
+            .find(|(_, written)| *written <= revision)
+            .map(|(value, _)| value)
+    }
+
+    /// The revisions at which the values that the cell remembers were written, oldest first,
+    /// ending with the revision of the current value. Empty if the cell doesn't have a history.
+    pub(crate) fn history_revisions(&self) -> Vec<Revision> {
+        match &self.history {
+            Some(history) => history
+                .entries
+                .iter()
+                .map(|(_, revision)| *revision)
+                .chain(std::iter::once(self.revision()))
+                .collect(),
+            None => vec![],
+        }
+    }
+}
+
+impl<T> ReactiveCell<T>
+where
+    T: Clone + 'static,
+{
+    pub(crate) fn read(&self) -> T {
+        self.value.clone()
+    }
 }
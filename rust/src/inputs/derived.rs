@@ -3,20 +3,39 @@ use std::{fmt::Debug, sync::Arc};
 use derive_new::new;
 use parking_lot::{Mutex, MutexGuard};
 
-use crate::{timeline::EvaluationContext, timeline::Revision};
+use crate::{
+    timeline::{DynId, EvaluationContext, Mode, Revision},
+    Error,
+};
 
 use super::{Reactive, ReactiveTag};
 
+/// Brings the memo identified by the `DynId` up to date, without tracking it as a dependency.
+pub(crate) type Refresh = fn(&mut EvaluationContext, DynId) -> Result<(), Error>;
+
 #[derive(Debug, Default, Clone)]
 pub struct DerivedTagData {
-    deps: Vec<ReactiveTag>,
+    /// The inputs that the last computation consumed, along with their tags.
+    deps: Vec<(DynId, ReactiveTag)>,
+    /// The memos among `deps`, which have to be refreshed before `deps` can be trusted, because a
+    /// refreshed memo may turn out to be backdated.
+    memos: Vec<(DynId, Refresh)>,
+    /// The constants that the last computation consumed. A constant can't invalidate the
+    /// computation, so they're only kept for `Timeline::dependency_graph`.
+    constants: Vec<DynId>,
     initialized: bool,
     modifying: bool,
+    /// `(validated, reported)`: the last recomputation, at `validated`, produced the same value as
+    /// the one that was already computed at `reported`. As long as the dependencies haven't changed
+    /// since `validated`, the tag reports the older revision.
+    backdated: Option<(Revision, Revision)>,
 }
 
 impl DerivedTagData {
     pub(crate) fn reset(&mut self) {
         self.deps.clear();
+        self.memos.clear();
+        self.constants.clear();
         self.modifying = true;
     }
 
@@ -25,15 +44,32 @@ impl DerivedTagData {
     }
 
     pub(crate) fn revision(&self) -> Revision {
+        let revision = self.deps_revision();
+
+        match self.backdated {
+            Some((validated, reported)) if revision <= validated => reported,
+            _ => revision,
+        }
+    }
+
+    pub(crate) fn deps_revision(&self) -> Revision {
         self.deps
             .iter()
-            .map(|d| d.revision())
+            .map(|(_, d)| d.revision())
             .max()
             .unwrap_or(Revision::constant())
     }
 
-    pub(crate) fn add_dep(&mut self, tag: ReactiveTag) {
-        self.deps.push(tag);
+    pub(crate) fn add_dep(&mut self, id: DynId, tag: ReactiveTag) {
+        self.deps.push((id, tag));
+    }
+
+    pub(crate) fn add_memo(&mut self, id: DynId, refresh: Refresh) {
+        self.memos.push((id, refresh));
+    }
+
+    pub(crate) fn add_constant(&mut self, id: DynId) {
+        self.constants.push(id);
     }
 }
 
@@ -43,7 +79,7 @@ pub struct DerivedTag {
 }
 
 impl DerivedTag {
-    fn assert_not_modifying(&self, operation: &'static str) -> MutexGuard<DerivedTagData> {
+    fn assert_not_modifying(&self, operation: &'static str) -> MutexGuard<'_, DerivedTagData> {
         let tag = self.tag.lock();
 
         if tag.modifying {
@@ -53,7 +89,7 @@ impl DerivedTag {
         tag
     }
 
-    fn assert_modifying(&self, operation: &'static str) -> MutexGuard<DerivedTagData> {
+    fn assert_modifying(&self, operation: &'static str) -> MutexGuard<'_, DerivedTagData> {
         let tag = self.tag.lock();
 
         if !tag.modifying {
@@ -78,20 +114,65 @@ impl DerivedTag {
         self.assert_not_modifying("get the revision").revision()
     }
 
-    pub(crate) fn add_dep(&self, tag: ReactiveTag) {
-        self.assert_modifying("add a dependency").add_dep(tag);
+    /// The revision of the dependencies, ignoring backdating.
+    pub(crate) fn deps_revision(&self) -> Revision {
+        self.assert_not_modifying("get the revision")
+            .deps_revision()
+    }
+
+    pub(crate) fn add_dep(&self, id: DynId, tag: ReactiveTag) {
+        self.assert_modifying("add a dependency").add_dep(id, tag);
+    }
+
+    /// The inputs that the last computation consumed, in the order that it consumed them.
+    pub(crate) fn dependencies(&self) -> Vec<(DynId, ReactiveTag)> {
+        self.assert_not_modifying("get the dependencies")
+            .deps
+            .clone()
+    }
+
+    pub(crate) fn add_memo(&self, id: DynId, refresh: Refresh) {
+        self.assert_modifying("add a memo").add_memo(id, refresh);
+    }
+
+    pub(crate) fn memos(&self) -> Vec<(DynId, Refresh)> {
+        self.assert_not_modifying("get the memos").memos.clone()
+    }
+
+    pub(crate) fn add_constant(&self, id: DynId) {
+        self.assert_modifying("add a constant").add_constant(id);
+    }
+
+    /// The constants that the last computation consumed, in the order that it consumed them.
+    pub(crate) fn constants(&self) -> Vec<DynId> {
+        self.assert_not_modifying("get the constants")
+            .constants
+            .clone()
+    }
+
+    /// Take over the dependencies that a recomputation collected in `computed`.
+    pub(crate) fn replace_deps(&self, computed: DerivedTag) {
+        let computed = std::mem::take(&mut *computed.assert_not_modifying("replace a tag"));
+        let mut tag = self.assert_not_modifying("replace the dependencies");
+        tag.deps = computed.deps;
+        tag.memos = computed.memos;
+        tag.constants = computed.constants;
+    }
+
+    pub(crate) fn backdate(&self, backdated: Option<(Revision, Revision)>) {
+        self.assert_not_modifying("backdate").backdated = backdated;
     }
 }
 
-impl Into<ReactiveTag> for DerivedTag {
-    fn into(self) -> ReactiveTag {
-        ReactiveTag::Derived(self)
+impl From<DerivedTag> for ReactiveTag {
+    fn from(tag: DerivedTag) -> ReactiveTag {
+        ReactiveTag::Derived(tag)
     }
 }
 
 pub trait DynamicComputation<T>
 where
-    T: Debug + Clone + 'static,
+    T: 'static,
 {
     fn compute(&self, ctx: &mut EvaluationContext) -> T;
 }
@@ -99,41 +180,154 @@ where
 impl<T, U> DynamicComputation<T> for U
 where
     U: Fn(&mut EvaluationContext) -> T,
-    T: Debug + Clone + 'static,
+    T: 'static,
 {
     fn compute(&self, ctx: &mut EvaluationContext) -> T {
         self(ctx)
     }
 }
 
+#[derive(Debug)]
+struct Memoized<T> {
+    value: T,
+    /// The revision of the dependencies when the value was computed.
+    revision: Revision,
+    /// The revision at which the value last changed. Only differs from `revision` for a backdated
+    /// memo.
+    changed_at: Revision,
+}
+
+struct Memo<T> {
+    /// The last computed value, once it was computed.
+    memoized: Mutex<Option<Memoized<T>>>,
+    /// A memo hands out copies of its value, so only values that can be cloned can be memoized.
+    clone: fn(&T) -> T,
+    /// For a backdated memo, decides whether a recomputed value is the same as the memoized one.
+    cutoff: Option<fn(&T, &T) -> bool>,
+}
+
 #[derive(new)]
-pub(crate) struct ReactiveDerived<T: Debug + Clone + 'static> {
+pub(crate) struct ReactiveDerived<T: 'static, M: Mode> {
     tag: DerivedTag,
-    computation: Box<dyn DynamicComputation<T>>,
+    computation: Box<M::Computation<T>>,
+    /// `None` for a plain derived computation, which recomputes on every read.
+    #[new(default)]
+    memo: Option<Memo<T>>,
 }
 
-impl<T: Debug + Clone + 'static> ReactiveDerived<T> {
+impl<T: 'static, M: Mode> ReactiveDerived<T, M> {
+    pub(crate) fn memo(
+        tag: DerivedTag,
+        computation: Box<M::Computation<T>>,
+    ) -> ReactiveDerived<T, M>
+    where
+        T: Clone,
+    {
+        ReactiveDerived {
+            tag,
+            computation,
+            memo: Some(Memo {
+                memoized: Mutex::new(None),
+                clone: Clone::clone,
+                cutoff: None,
+            }),
+        }
+    }
+
+    pub(crate) fn backdated_memo(
+        tag: DerivedTag,
+        computation: Box<M::Computation<T>>,
+    ) -> ReactiveDerived<T, M>
+    where
+        T: Clone + PartialEq,
+    {
+        ReactiveDerived {
+            tag,
+            computation,
+            memo: Some(Memo {
+                memoized: Mutex::new(None),
+                clone: Clone::clone,
+                cutoff: Some(PartialEq::eq),
+            }),
+        }
+    }
+
     pub(crate) fn compute(&self, ctx: &mut EvaluationContext) -> T {
         self.computation.compute(ctx)
     }
 
+    pub(crate) fn is_memo(&self) -> bool {
+        self.memo.is_some()
+    }
+
+    pub(crate) fn derived_tag(&self) -> &DerivedTag {
+        &self.tag
+    }
+
+    /// If this is a memoized computation, and none of the dependencies of its last computation
+    /// changed since then, return the memoized value.
+    pub(crate) fn memoized(&self) -> Option<T> {
+        let memo = self.memo.as_ref()?;
+        let memoized = memo.memoized.lock();
+        let memoized = memoized.as_ref()?;
+
+        if self.tag.deps_revision() <= memoized.revision {
+            Some((memo.clone)(&memoized.value))
+        } else {
+            None
+        }
+    }
+
+    /// If this is a memoized computation, remember the value that it just computed, along with the
+    /// revision of its dependencies. If this is a backdated memo and the value didn't change, the
+    /// tag keeps reporting the revision of the previous value, so that its consumers stay valid.
+    pub(crate) fn memoize(&self, value: &T) {
+        if let Some(memo) = &self.memo {
+            let mut memoized = memo.memoized.lock();
+            let revision = self.tag.deps_revision();
+
+            let changed_at = match (&*memoized, memo.cutoff) {
+                (Some(previous), Some(eq)) if eq(&previous.value, value) => previous.changed_at,
+                _ => revision,
+            };
+
+            self.tag.backdate(if changed_at < revision {
+                Some((revision, changed_at))
+            } else {
+                None
+            });
+
+            *memoized = Some(Memoized {
+                value: (memo.clone)(value),
+                revision,
+                changed_at,
+            });
+        }
+    }
+
     pub(crate) fn revision(&self) -> Revision {
         self.get_tag().revision()
     }
 
+    /// Compute with a fresh tag, and only move the dependencies that it collected into this
+    /// derived's tag once the computation is done. A render on another thread that reads this
+    /// derived in the meantime keeps seeing the dependencies of the last computation, instead of a
+    /// tag that's being modified.
     pub(crate) fn reset_tag<U>(&self, cb: impl FnOnce(DerivedTag) -> U) -> U {
-        let tag = self.tag.clone();
+        let tag = DerivedTag::default();
 
         tag.reset();
         let result = cb(tag.clone());
         tag.done();
+        self.tag.replace_deps(tag);
         result
     }
 }
 
-impl<T> Reactive for ReactiveDerived<T>
+impl<T, M> Reactive for ReactiveDerived<T, M>
 where
-    T: Debug + Clone + 'static,
+    T: 'static,
+    M: Mode,
 {
     fn get_tag(&self) -> ReactiveTag {
         ReactiveTag::Derived(self.tag.clone())
@@ -142,7 +336,7 @@ where
 
 // impl<T> ReactiveCompute for ReactiveDerived<T>
 // where
-//     T: Debug + Clone + 'static,
+//     T: 'static,
 // {
 //     fn get_internal_tag(&self) -> &Option<DerivedTag> {
 //         &self.tag
@@ -153,9 +347,10 @@ where
 //     }
 // }
 
-impl<T> Debug for ReactiveDerived<T>
+impl<T, M> Debug for ReactiveDerived<T, M>
 where
-    T: Debug + Clone + 'static,
+    T: 'static,
+    M: Mode,
 {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(f, "ReactiveComputation<{:?}>", std::any::type_name::<T>())
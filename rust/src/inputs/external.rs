@@ -0,0 +1,82 @@
+use std::{fmt::Debug, sync::Arc};
+
+use crate::timeline::{Mode, Revision};
+
+use super::{Reactive, ReactiveTag, Tag};
+
+/// A source of values that lives outside of the timeline, such as a cache or a file-backed store.
+///
+/// The timeline never copies the value into a cell. Instead, it reads the value from the storage
+/// whenever a computation asks for it, and it relies on the owner of the storage to dirty the
+/// storage's tag (through `UpdateTransaction::dirty`) whenever the value changes.
+pub trait ExternalStorage<T>
+where
+    T: 'static,
+{
+    fn read(&self) -> T;
+}
+
+impl<T, U> ExternalStorage<T> for U
+where
+    U: Fn() -> T,
+    T: 'static,
+{
+    fn read(&self) -> T {
+        self()
+    }
+}
+
+pub(crate) struct ReactiveExternal<T, M>
+where
+    T: 'static,
+    M: Mode,
+{
+    storage: Box<M::Storage<T>>,
+    tag: Arc<Tag>,
+}
+
+impl<T, M> ReactiveExternal<T, M>
+where
+    T: 'static,
+    M: Mode,
+{
+    pub(crate) fn new(storage: Box<M::Storage<T>>, revision: Revision) -> Self {
+        ReactiveExternal {
+            storage,
+            tag: Tag::arc(revision.atomic()),
+        }
+    }
+
+    pub(crate) fn read(&self) -> T {
+        self.storage.read()
+    }
+
+    /// Record that the value in the storage changed at `revision`.
+    pub(crate) fn dirty(&self, revision: Revision) {
+        self.tag.revision.update(revision);
+    }
+
+    pub(crate) fn revision(&self) -> Revision {
+        self.tag.revision.get()
+    }
+}
+
+impl<T, M> Reactive for ReactiveExternal<T, M>
+where
+    T: 'static,
+    M: Mode,
+{
+    fn get_tag(&self) -> ReactiveTag {
+        ReactiveTag::Tag(self.tag.clone())
+    }
+}
+
+impl<T, M> Debug for ReactiveExternal<T, M>
+where
+    T: 'static,
+    M: Mode,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "ReactiveExternal<{:?}>", std::any::type_name::<T>())
+    }
+}
@@ -1,4 +1,8 @@
-use std::fmt::Debug;
+use std::{collections::HashSet, fmt::Debug};
+
+use getset::{CopyGetters, Getters};
+
+use crate::{timeline::Revision, Error};
 
 use super::DerivedTag;
 
@@ -31,26 +35,152 @@ impl Key {
     }
 }
 
-pub(crate) struct KeyedItem<Item: Debug + Clone + 'static> {
+#[derive(Debug, Clone, PartialEq, Getters, CopyGetters)]
+pub struct KeyedItem<Item: 'static> {
+    #[get = "pub"]
     key: Key,
+    #[get = "pub"]
     item: Item,
+    /// The point on the timeline when this item was inserted into its list, or last replaced.
+    #[get_copy = "pub"]
+    revision: Revision,
 }
 
-pub struct ReactiveList<T: Debug + Clone + 'static> {
+#[derive(Debug, Clone, PartialEq)]
+pub struct ReactiveList<T: 'static> {
     items: Vec<KeyedItem<T>>,
 }
 
+impl<T: 'static> ReactiveList<T> {
+    pub fn len(&self) -> usize {
+        self.items.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+
+    pub fn items(&self) -> &[KeyedItem<T>] {
+        &self.items
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &T> {
+        self.items.iter().map(|keyed| &keyed.item)
+    }
+
+    pub fn keys(&self) -> impl Iterator<Item = &Key> {
+        self.items.iter().map(|keyed| &keyed.key)
+    }
+
+    pub fn position(&self, key: &Key) -> Option<usize> {
+        self.items.iter().position(|keyed| &keyed.key == key)
+    }
+
+    pub fn get(&self, key: &Key) -> Option<&T> {
+        self.position(key).map(|index| &self.items[index].item)
+    }
+
+    /// Record `revision` as the revision of every item in the list. This is used when a list is
+    /// first installed into a timeline, so it also rejects a list with duplicate keys, just like
+    /// `insert` would.
+    pub(crate) fn stamp(&mut self, revision: Revision) {
+        let mut keys = HashSet::new();
+
+        for keyed in &mut self.items {
+            if !keys.insert(&keyed.key) {
+                already_inserted(&keyed.key);
+            }
+
+            keyed.revision = revision;
+        }
+    }
+
+    pub(crate) fn insert(
+        &mut self,
+        index: usize,
+        key: Key,
+        item: T,
+        revision: Revision,
+    ) -> Result<(), Error> {
+        if self.position(&key).is_some() {
+            return Err(Error::DuplicateKey { key });
+        }
+
+        if index > self.items.len() {
+            return Err(Error::OutOfBounds {
+                index,
+                len: self.items.len(),
+            });
+        }
+
+        self.items.insert(
+            index,
+            KeyedItem {
+                key,
+                item,
+                revision,
+            },
+        );
+
+        Ok(())
+    }
+
+    pub(crate) fn push(&mut self, key: Key, item: T, revision: Revision) -> Result<(), Error> {
+        self.insert(self.items.len(), key, item, revision)
+    }
+
+    pub(crate) fn remove(&mut self, key: &Key) -> Result<T, Error> {
+        let index = self.try_position(key)?;
+        Ok(self.items.remove(index).item)
+    }
+
+    /// Move the item identified by `key` to `index`, where `index` is the item's position after
+    /// the move.
+    pub(crate) fn move_to(&mut self, key: &Key, index: usize) -> Result<(), Error> {
+        let from = self.try_position(key)?;
+
+        // the item is removed before it's inserted again, so the last position is `len - 1`
+        if index >= self.items.len() {
+            return Err(Error::OutOfBounds {
+                index,
+                len: self.items.len(),
+            });
+        }
+
+        let keyed = self.items.remove(from);
+        self.items.insert(index, keyed);
+        Ok(())
+    }
+
+    pub(crate) fn replace(&mut self, key: &Key, item: T, revision: Revision) -> Result<(), Error> {
+        let index = self.try_position(key)?;
+        let keyed = &mut self.items[index];
+        keyed.item = item;
+        keyed.revision = revision;
+        Ok(())
+    }
+
+    fn try_position(&self, key: &Key) -> Result<usize, Error> {
+        self.position(key)
+            .ok_or_else(|| Error::MissingKey { key: key.clone() })
+    }
+}
+
+fn already_inserted(key: &Key) -> ! {
+    panic!("{}", Error::DuplicateKey { key: key.clone() })
+}
+
 pub trait GetReactiveKey {
     fn get_reactive_key(&self) -> Key;
 }
 
-pub trait CopyIntoReactiveList<Item: Debug + Clone + 'static> {
+pub trait CopyIntoReactiveList<Item: 'static> {
     fn copy_into_reactive_list(&self) -> ReactiveList<Item>;
 }
 
 impl<T> CopyIntoReactiveList<T> for Vec<T>
 where
-    T: Debug + Clone + GetReactiveKey + 'static,
+    T: Clone + GetReactiveKey + 'static,
 {
     fn copy_into_reactive_list(&self) -> ReactiveList<T> {
         ReactiveList {
@@ -59,13 +189,14 @@ where
                 .map(|item| KeyedItem {
                     key: item.get_reactive_key(),
                     item: item.clone(),
+                    revision: Revision::constant(),
                 })
                 .collect(),
         }
     }
 }
 
-pub(crate) struct ReactiveIterable<T: Debug + Clone + 'static> {
+pub(crate) struct ReactiveIterable<T: 'static> {
     iterable: Box<dyn CopyIntoReactiveList<T>>,
     tag: DerivedTag,
 }
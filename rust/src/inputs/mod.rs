@@ -1,11 +1,14 @@
 pub(crate) mod cell;
 pub(crate) mod derived;
+pub(crate) mod external;
 pub(crate) mod iterable;
 pub mod reactive;
 
 pub(crate) use cell::{ReactiveCell, Tag};
 pub use derived::DynamicComputation;
-pub(crate) use derived::{DerivedTag, ReactiveDerived};
-pub use iterable::{GetReactiveKey, Key};
+pub(crate) use derived::{DerivedTag, ReactiveDerived, Refresh};
+pub use external::ExternalStorage;
+pub(crate) use external::ReactiveExternal;
+pub use iterable::{CopyIntoReactiveList, GetReactiveKey, Key, KeyedItem, ReactiveList};
 pub use reactive::Reactive;
 pub(crate) use reactive::ReactiveTag;
@@ -17,6 +17,12 @@ impl ReactiveTag {
             ReactiveTag::Derived(tag) => tag.revision(),
         }
     }
+
+    /// A tag is constant if it's a constant cell, or a derived computation whose most recent
+    /// dependencies were all constant. A constant tag never changes again.
+    pub(crate) fn is_constant(&self) -> bool {
+        self.revision().is_constant()
+    }
 }
 
 pub trait Reactive {
@@ -1,9 +1,13 @@
 #![allow(dead_code)]
 
+pub(crate) mod error;
+#[macro_use]
+pub(crate) mod trace;
 #[macro_use]
 pub mod inputs;
 pub mod outputs;
 pub mod timeline;
 
-pub use inputs::{GetReactiveKey, Key, Reactive};
+pub use error::Error;
+pub use inputs::{GetReactiveKey, Key, Reactive, ReactiveList};
 pub use timeline::{Revision, TypedInputId};
@@ -0,0 +1,238 @@
+use std::{collections::HashMap, fmt::Debug};
+
+use getset::Getters;
+
+use crate::{
+    inputs::{KeyedItem, ReactiveList},
+    timeline::{DynId, Explanation, RenderTransaction, Revision, TypedInputId},
+    Key,
+};
+
+use super::Output;
+
+/// A single change to a `ListOutput`. Applying the changes from one update in order turns the
+/// previously rendered list into the new one.
+///
+/// `before` is the key of the item that the inserted or moved item must be placed immediately
+/// before, or `None` to place it at the end of the list.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ListChange<T> {
+    Insert {
+        key: Key,
+        item: T,
+        before: Option<Key>,
+    },
+    Move {
+        key: Key,
+        before: Option<Key>,
+    },
+    Update {
+        key: Key,
+        item: T,
+    },
+    Remove {
+        key: Key,
+    },
+}
+
+#[derive(Debug, Getters)]
+pub struct ListOutput<T: Debug + Clone + PartialEq + 'static> {
+    list: TypedInputId<ReactiveList<T>>,
+    /// The items, as of the last update.
+    #[get = "pub"]
+    items: Vec<KeyedItem<T>>,
+    /// The changes that the last update made to `items`.
+    #[get = "pub"]
+    changes: Vec<ListChange<T>>,
+    last_revision: Option<Revision>,
+}
+
+impl<T: Debug + Clone + PartialEq + 'static> ListOutput<T> {
+    pub(crate) fn new(list: TypedInputId<ReactiveList<T>>) -> ListOutput<T> {
+        ListOutput {
+            list,
+            items: vec![],
+            changes: vec![],
+            last_revision: None,
+        }
+    }
+
+    /// Returns true if the list backing this output changed since the last time the output was
+    /// updated. This only validates revisions, and never reads the list.
+    pub fn is_stale(&self, timeline: &RenderTransaction) -> bool {
+        match self.last_revision {
+            None => true,
+            Some(last) => match timeline.revision(self.list) {
+                None => true,
+                Some(current) => current > last,
+            },
+        }
+    }
+
+    /// Update the output if its list is stale, and return whether anything changed. The changes
+    /// themselves are available through `changes()` until the next update.
+    pub fn update(&mut self, timeline: &mut RenderTransaction) -> bool {
+        self.changes.clear();
+
+        if !self.is_stale(timeline) {
+            return false;
+        }
+
+        let list = timeline.value(self.list);
+        self.changes = diff(&self.items, list.items());
+        self.items = list.items().to_vec();
+        self.last_revision = timeline.revision(self.list);
+
+        !self.changes.is_empty()
+    }
+
+    pub fn value(&self) -> Vec<T> {
+        self.items
+            .iter()
+            .map(|keyed| keyed.item().clone())
+            .collect()
+    }
+}
+
+/// Compute the changes that turn `old` into `new`.
+///
+/// Items that only exist in `old` are removed first. Then, items that exist in both lists are
+/// left in place if they belong to the longest run of items whose relative order didn't change,
+/// and moved otherwise, so the number of moves is minimal. Finally, the new list is walked from
+/// the end, so each inserted or moved item can be placed before an item that is already in its
+/// final position.
+fn diff<T: Debug + Clone + PartialEq + 'static>(
+    old: &[KeyedItem<T>],
+    new: &[KeyedItem<T>],
+) -> Vec<ListChange<T>> {
+    let mut changes = vec![];
+
+    let new_keys: HashMap<&Key, usize> = new
+        .iter()
+        .enumerate()
+        .map(|(index, keyed)| (keyed.key(), index))
+        .collect();
+
+    for keyed in old {
+        if !new_keys.contains_key(keyed.key()) {
+            changes.push(ListChange::Remove {
+                key: keyed.key().clone(),
+            });
+        }
+    }
+
+    let old_items: HashMap<&Key, (usize, &KeyedItem<T>)> = old
+        .iter()
+        .enumerate()
+        .map(|(index, keyed)| (keyed.key(), (index, keyed)))
+        .collect();
+
+    // the position in `old` of every item in `new`, or `None` if the item is new
+    let old_positions: Vec<Option<usize>> = new
+        .iter()
+        .map(|keyed| old_items.get(keyed.key()).map(|(index, _)| *index))
+        .collect();
+
+    let stable = longest_increasing_subsequence(&old_positions);
+
+    for (index, keyed) in new.iter().enumerate().rev() {
+        let key = keyed.key().clone();
+        let before = new.get(index + 1).map(|next| next.key().clone());
+
+        match old_items.get(keyed.key()) {
+            None => changes.push(ListChange::Insert {
+                key,
+                item: keyed.item().clone(),
+                before,
+            }),
+            Some((_, previous)) => {
+                if !stable[index] {
+                    changes.push(ListChange::Move {
+                        key: key.clone(),
+                        before,
+                    });
+                }
+
+                if changed(keyed, previous) {
+                    changes.push(ListChange::Update {
+                        key,
+                        item: keyed.item().clone(),
+                    });
+                }
+            }
+        }
+    }
+
+    changes
+}
+
+/// Returns true if an item was replaced since `previous`. Items of a list that was copied out of
+/// a derived computation all have a constant revision, so those are compared by value instead.
+fn changed<T: PartialEq>(keyed: &KeyedItem<T>, previous: &KeyedItem<T>) -> bool {
+    if keyed.revision().is_constant() && previous.revision().is_constant() {
+        keyed.item() != previous.item()
+    } else {
+        keyed.revision() != previous.revision()
+    }
+}
+
+/// Given the old positions of the items in a list, return which of the items are part of the
+/// longest subsequence whose old positions are increasing. Items without an old position are
+/// never part of the subsequence.
+fn longest_increasing_subsequence(positions: &[Option<usize>]) -> Vec<bool> {
+    // `tails[n]` is the index (into `positions`) of the smallest tail of an increasing subsequence
+    // of length `n + 1`
+    let mut tails: Vec<usize> = vec![];
+    let mut predecessors: Vec<Option<usize>> = vec![None; positions.len()];
+
+    for (index, position) in positions.iter().enumerate() {
+        let position = match position {
+            Some(position) => *position,
+            None => continue,
+        };
+
+        let length = tails.partition_point(|tail| positions[*tail] < Some(position));
+
+        if length > 0 {
+            predecessors[index] = Some(tails[length - 1]);
+        }
+
+        if length == tails.len() {
+            tails.push(index);
+        } else {
+            tails[length] = index;
+        }
+    }
+
+    let mut stable = vec![false; positions.len()];
+    let mut next = tails.last().copied();
+
+    while let Some(index) = next {
+        stable[index] = true;
+        next = predecessors[index];
+    }
+
+    stable
+}
+
+impl<T: Debug + Clone + PartialEq + 'static> Output for ListOutput<T> {
+    fn is_stale(&self, timeline: &RenderTransaction) -> bool {
+        ListOutput::is_stale(self, timeline)
+    }
+
+    fn update(&mut self, timeline: &mut RenderTransaction) -> bool {
+        ListOutput::update(self, timeline)
+    }
+
+    fn dependencies(&self) -> Vec<DynId> {
+        vec![self.list.into()]
+    }
+
+    fn explain(&self, timeline: &RenderTransaction) -> Vec<Explanation> {
+        self.last_revision
+            .and_then(|since| timeline.try_explain(self.list, since).ok())
+            .filter(Explanation::is_stale)
+            .into_iter()
+            .collect()
+    }
+}
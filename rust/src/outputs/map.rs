@@ -0,0 +1,186 @@
+use std::{fmt::Debug, hash::Hash};
+
+use getset::Getters;
+use indexmap::IndexMap;
+
+use crate::timeline::{DynId, Explanation, RenderTransaction, TypedInputId};
+
+use super::{Output, PrimitiveOutput};
+
+/// A single change to a `MapOutput`, reported by the update that observed it.
+#[derive(Debug, Clone, PartialEq)]
+pub enum MapChange<K, V> {
+    Insert { key: K, value: V },
+    Update { key: K, value: V },
+    Remove { key: K },
+}
+
+#[derive(Debug)]
+struct MapEntry<V: Debug + Clone + 'static> {
+    // once the entry was rendered, the output always has a value
+    output: PrimitiveOutput<V>,
+    // whether a consumer has already been told about this entry
+    rendered: bool,
+}
+
+/// A map whose entries are individually reactive. Each entry is backed by its own input, so an
+/// update only reports the entries that were inserted, removed or changed since the last update,
+/// and never touches the rest.
+///
+/// Entries are inserted and removed directly on the output, and those changes are reported by the
+/// next update. Once the map is registered with a timeline's program, it's reached through
+/// `Timeline::program_mut` and `Program::get_mut`, and `Timeline::render` reports the changes.
+#[derive(Debug, Getters)]
+pub struct MapOutput<K, V>
+where
+    K: Debug + Clone + Hash + Eq + 'static,
+    V: Debug + Clone + 'static,
+{
+    entries: IndexMap<K, MapEntry<V>>,
+    // rendered entries that were removed since the last update, with their last rendered values
+    removed: Vec<(K, V)>,
+    /// The changes that the last update reported.
+    #[get = "pub"]
+    changes: Vec<MapChange<K, V>>,
+}
+
+impl<K, V> Default for MapOutput<K, V>
+where
+    K: Debug + Clone + Hash + Eq + 'static,
+    V: Debug + Clone + 'static,
+{
+    fn default() -> MapOutput<K, V> {
+        MapOutput::new()
+    }
+}
+
+impl<K, V> MapOutput<K, V>
+where
+    K: Debug + Clone + Hash + Eq + 'static,
+    V: Debug + Clone + 'static,
+{
+    pub fn new() -> MapOutput<K, V> {
+        MapOutput {
+            entries: IndexMap::new(),
+            removed: vec![],
+            changes: vec![],
+        }
+    }
+
+    /// Back the entry for `key` with `input`. If the entry already existed, the next update
+    /// reports it as changed, and until then, the entry keeps its last rendered value.
+    pub fn insert(&mut self, key: K, input: impl Into<TypedInputId<V>>) {
+        let rendered = match self.entries.get(&key) {
+            Some(entry) if entry.rendered => Some(entry.output.value()),
+            Some(_) => None,
+            None => match self.removed.iter().position(|(removed, _)| removed == &key) {
+                Some(index) => Some(self.removed.remove(index).1),
+                None => None,
+            },
+        };
+
+        let entry = MapEntry {
+            rendered: rendered.is_some(),
+            output: PrimitiveOutput::new(rendered, input.into()),
+        };
+
+        self.entries.insert(key, entry);
+    }
+
+    /// Remove the entry for `key`. If a previous update already reported the entry, the next update
+    /// reports its removal.
+    pub fn remove(&mut self, key: &K) {
+        if let Some(entry) = self.entries.shift_remove(key) {
+            if entry.rendered {
+                self.removed.push((key.clone(), entry.output.value()));
+            }
+        }
+    }
+
+    pub fn contains_key(&self, key: &K) -> bool {
+        self.entries.contains_key(key)
+    }
+
+    /// The value of the entry for `key`, as of the last update.
+    pub fn get(&self, key: &K) -> Option<V> {
+        match self.entries.get(key) {
+            Some(entry) if entry.rendered => Some(entry.output.value()),
+            _ => None,
+        }
+    }
+
+    /// All of the entries, as of the last update.
+    pub fn value(&self) -> IndexMap<K, V> {
+        self.entries
+            .iter()
+            .filter(|(_, entry)| entry.rendered)
+            .map(|(key, entry)| (key.clone(), entry.output.value()))
+            .collect()
+    }
+
+    /// Returns true if any entry was inserted or removed, or if the input of any entry changed,
+    /// since the last update.
+    pub fn is_stale(&self, timeline: &RenderTransaction) -> bool {
+        !self.removed.is_empty()
+            || self
+                .entries
+                .values()
+                .any(|entry| entry.output.is_stale(timeline))
+    }
+
+    /// Update every stale entry, and return whether anything changed. The changes themselves are
+    /// available through `changes()` until the next update.
+    pub fn update(&mut self, timeline: &mut RenderTransaction) -> bool {
+        self.changes.clear();
+
+        for (key, _) in self.removed.drain(..) {
+            self.changes.push(MapChange::Remove { key });
+        }
+
+        for (key, entry) in &mut self.entries {
+            if !entry.output.update(timeline) {
+                continue;
+            }
+
+            let key = key.clone();
+            let value = entry.output.value();
+
+            if entry.rendered {
+                self.changes.push(MapChange::Update { key, value });
+            } else {
+                entry.rendered = true;
+                self.changes.push(MapChange::Insert { key, value });
+            }
+        }
+
+        !self.changes.is_empty()
+    }
+}
+
+impl<K, V> Output for MapOutput<K, V>
+where
+    K: Debug + Clone + Hash + Eq + 'static,
+    V: Debug + Clone + 'static,
+{
+    fn is_stale(&self, timeline: &RenderTransaction) -> bool {
+        MapOutput::is_stale(self, timeline)
+    }
+
+    fn update(&mut self, timeline: &mut RenderTransaction) -> bool {
+        MapOutput::update(self, timeline)
+    }
+
+    fn dependencies(&self) -> Vec<DynId> {
+        self.entries
+            .values()
+            .flat_map(|entry| entry.output.dependencies())
+            .collect()
+    }
+
+    fn explain(&self, timeline: &RenderTransaction) -> Vec<Explanation> {
+        self.entries
+            .values()
+            .flat_map(|entry| entry.output.explain(timeline))
+            .collect()
+    }
+}
@@ -1,3 +1,15 @@
+pub(crate) mod list;
+pub(crate) mod map;
+pub(crate) mod output;
 pub(crate) mod primitive;
+pub(crate) mod program;
+pub(crate) mod set;
+pub(crate) mod tree;
 
+pub use list::{ListChange, ListOutput};
+pub use map::{MapChange, MapOutput};
+pub use output::Output;
 pub use primitive::PrimitiveOutput;
+pub use program::{OutputId, Program};
+pub use set::{SetChange, SetOutput};
+pub use tree::{TreeChild, TreeOutput};
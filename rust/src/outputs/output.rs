@@ -0,0 +1,28 @@
+use std::{any::Any, fmt::Debug};
+
+use crate::timeline::{DynId, Explanation, RenderTransaction};
+
+/// A reactive output node. An output remembers the revisions of the inputs it last consumed, so it
+/// can determine whether it's stale without computing anything.
+pub trait Output: Any + Debug {
+    /// Returns true if any of the inputs of this output changed since it was last updated.
+    fn is_stale(&self, timeline: &RenderTransaction) -> bool;
+
+    /// Update the output if it's stale, and return whether it changed.
+    fn update(&mut self, timeline: &mut RenderTransaction) -> bool;
+
+    /// The inputs that this output reads, for `Timeline::dependency_graph`.
+    fn dependencies(&self) -> Vec<DynId> {
+        vec![]
+    }
+
+    /// Explain every input of this output that changed since the output was last updated. An
+    /// output that was never updated doesn't have anything to explain.
+    fn explain(&self, _timeline: &RenderTransaction) -> Vec<Explanation> {
+        vec![]
+    }
+
+    fn type_name(&self) -> &'static str {
+        std::any::type_name::<Self>()
+    }
+}
@@ -3,22 +3,59 @@ use std::fmt::Debug;
 use derive_new::new;
 use getset::Getters;
 
-use crate::timeline::{RenderTransaction, TypedInputId};
+use crate::timeline::{DynId, Explanation, RenderTransaction, Revision, TypedInputId};
+
+use super::Output;
 
 #[derive(Debug, Getters, new)]
-pub struct PrimitiveOutput<T: Debug + Clone + 'static> {
+pub struct PrimitiveOutput<T: 'static> {
     value: Option<T>,
     primitive: TypedInputId<T>,
+    /// The revision of `primitive` the last time this output demanded its value. `None` means that
+    /// the output has never been updated.
+    #[new(default)]
+    last_revision: Option<Revision>,
+}
+
+impl<T: 'static> PrimitiveOutput<T> {
+    /// Returns true if the input backing this output changed since the last time the output was
+    /// updated. This only validates revisions, and never computes the value of the input.
+    pub fn is_stale(&self, timeline: &RenderTransaction) -> bool {
+        match self.last_revision {
+            None => true,
+            // a constant input never changes, so we don't need to validate it again
+            Some(last) if last.is_constant() => false,
+            Some(last) => match timeline.revision(self.primitive) {
+                None => true,
+                Some(current) => current > last,
+            },
+        }
+    }
+
+    /// Returns true if the input backing this output was constant the last time the output was
+    /// updated, which means that the output will never be stale again.
+    pub fn is_constant(&self) -> bool {
+        self.last_revision.is_some_and(Revision::is_constant)
+    }
 }
 
-impl<T: Debug + Clone + 'static> PrimitiveOutput<T> {
+impl<T: Clone + 'static> PrimitiveOutput<T> {
     pub fn initialize(&mut self, timeline: &mut RenderTransaction) {
-        self.update(timeline)
+        self.update(timeline);
     }
 
-    pub fn update(&mut self, timeline: &mut RenderTransaction) {
+    /// Update the output if its input is stale, and return whether the value changed. A backdated
+    /// memo can turn out not to have changed once it's recomputed.
+    pub fn update(&mut self, timeline: &mut RenderTransaction) -> bool {
+        if !self.is_stale(timeline) {
+            return false;
+        }
+
         let new_value = timeline.value(self.primitive);
+        let last_revision = self.last_revision;
         self.value = Some(new_value);
+        self.last_revision = timeline.revision(self.primitive);
+        self.last_revision != last_revision
     }
 
     pub fn value(&self) -> T {
@@ -27,3 +64,25 @@ impl<T: Debug + Clone + 'static> PrimitiveOutput<T> {
             .expect("Cannot get an output's value before it was updated for the first time")
     }
 }
+
+impl<T: Debug + Clone + 'static> Output for PrimitiveOutput<T> {
+    fn is_stale(&self, timeline: &RenderTransaction) -> bool {
+        PrimitiveOutput::is_stale(self, timeline)
+    }
+
+    fn update(&mut self, timeline: &mut RenderTransaction) -> bool {
+        PrimitiveOutput::update(self, timeline)
+    }
+
+    fn dependencies(&self) -> Vec<DynId> {
+        vec![self.primitive.into()]
+    }
+
+    fn explain(&self, timeline: &RenderTransaction) -> Vec<Explanation> {
+        self.last_revision
+            .and_then(|since| timeline.try_explain(self.primitive, since).ok())
+            .filter(Explanation::is_stale)
+            .into_iter()
+            .collect()
+    }
+}
@@ -0,0 +1,163 @@
+use std::{
+    any::Any,
+    fmt::Debug,
+    marker::PhantomData,
+    sync::atomic::{AtomicU64, Ordering},
+};
+
+use crate::timeline::{DependencyGraph, GraphNode, Local, Mode, RenderTransaction, Storable};
+#[cfg(feature = "rayon")]
+use crate::timeline::{ParRenderTransaction, Shared};
+
+use super::Output;
+
+/// Hands out a different id to every program, so that an `OutputId` can't be used with a program
+/// that it doesn't belong to.
+static NEXT_PROGRAM: AtomicU64 = AtomicU64::new(0);
+
+/// A handle to an output that was registered with a `Program`.
+#[derive(Debug)]
+pub struct OutputId<O: Output> {
+    program: u64,
+    index: usize,
+    marker: PhantomData<O>,
+}
+
+impl<O: Output> Copy for OutputId<O> {}
+
+impl<O: Output> Clone for OutputId<O> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+/// All of the outputs of a program. Rendering the program updates every stale output, so nobody
+/// needs to update each output by hand.
+///
+/// The program of a `Timeline<Shared>` only holds outputs that are `Send + Sync`.
+#[derive(Debug)]
+pub struct Program<M: Mode = Local> {
+    id: u64,
+    outputs: Vec<Option<Box<M::Output>>>,
+}
+
+impl<M: Mode> Default for Program<M> {
+    fn default() -> Program<M> {
+        Program {
+            id: NEXT_PROGRAM.fetch_add(1, Ordering::Relaxed),
+            outputs: vec![],
+        }
+    }
+}
+
+impl<M: Mode> Program<M> {
+    pub(crate) fn register<O: Output + Storable<M>>(&mut self, output: O) -> OutputId<O> {
+        let index = self.outputs.len();
+        self.outputs.push(Some(output.into_boxed_output()));
+
+        OutputId {
+            program: self.id,
+            index,
+            marker: PhantomData,
+        }
+    }
+
+    pub(crate) fn unregister<O: Output>(&mut self, id: OutputId<O>) -> O {
+        self.check(id);
+
+        let output = self.outputs[id.index]
+            .take()
+            .expect("Attempted to unregister an output that was already unregistered");
+
+        let output: Box<dyn Any> = M::into_output(output);
+        *output
+            .downcast()
+            .expect("OutputId was used with the wrong program")
+    }
+
+    pub fn get<O: Output>(&self, id: OutputId<O>) -> &O {
+        self.check(id);
+
+        let output = self.outputs[id.index]
+            .as_ref()
+            .expect("Attempted to get an output that was unregistered");
+
+        let output: &dyn Any = M::as_output(output);
+        output
+            .downcast_ref()
+            .expect("OutputId was used with the wrong program")
+    }
+
+    pub fn get_mut<O: Output>(&mut self, id: OutputId<O>) -> &mut O {
+        self.check(id);
+
+        let output = self.outputs[id.index]
+            .as_mut()
+            .expect("Attempted to get an output that was unregistered");
+
+        let output: &mut dyn Any = M::as_output_mut(output);
+        output
+            .downcast_mut()
+            .expect("OutputId was used with the wrong program")
+    }
+
+    fn check<O: Output>(&self, id: OutputId<O>) {
+        assert_eq!(
+            id.program, self.id,
+            "OutputId was used with the wrong program"
+        );
+    }
+
+    pub fn outputs(&self) -> impl Iterator<Item = &dyn Output> {
+        self.outputs
+            .iter()
+            .flatten()
+            .map(|output| M::as_output(output))
+    }
+
+    pub(crate) fn add_to_graph(&self, graph: &mut DependencyGraph) {
+        for (index, output) in self.outputs.iter().enumerate() {
+            if let Some(output) = output {
+                let node = GraphNode::Output {
+                    index,
+                    type_name: output.type_name(),
+                };
+                graph.add_node(node);
+
+                for dependency in output.dependencies() {
+                    graph.add_edge(GraphNode::Input(dependency), node);
+                }
+            }
+        }
+    }
+
+    pub(crate) fn is_stale(&self, timeline: &RenderTransaction) -> bool {
+        self.outputs().any(|output| output.is_stale(timeline))
+    }
+
+    pub(crate) fn update(&mut self, timeline: &mut RenderTransaction) -> bool {
+        let mut updated = false;
+
+        for output in self.outputs.iter_mut().flatten() {
+            updated |= M::as_output_mut(output).update(timeline);
+        }
+
+        updated
+    }
+}
+
+#[cfg(feature = "rayon")]
+impl Program<Shared> {
+    pub(crate) fn par_update(&mut self, timeline: &ParRenderTransaction) -> bool {
+        use rayon::prelude::*;
+
+        self.outputs
+            .par_iter_mut()
+            .flatten()
+            .map_init(
+                || timeline.begin(),
+                |transaction, output| output.update(transaction),
+            )
+            .reduce(|| false, |left, right| left || right)
+    }
+}
@@ -0,0 +1,110 @@
+use std::{fmt::Debug, hash::Hash};
+
+use getset::Getters;
+use indexmap::IndexSet;
+
+use crate::timeline::{DynId, Explanation, RenderTransaction, TypedInputId};
+
+use super::{Output, PrimitiveOutput};
+
+/// A single change to a `SetOutput`, reported by the update that observed it.
+#[derive(Debug, Clone, PartialEq)]
+pub enum SetChange<T> {
+    Insert { member: T },
+    Remove { member: T },
+}
+
+/// A set of members, fed by a reactive collection (such as a `Vec` of class names). An update
+/// reports the members that were inserted or removed since the last update, so a consumer can
+/// update a class list without replacing it.
+#[derive(Debug, Getters)]
+pub struct SetOutput<T, C = Vec<T>>
+where
+    T: Debug + Clone + Hash + Eq + 'static,
+    C: Debug + Clone + IntoIterator<Item = T> + 'static,
+{
+    source: PrimitiveOutput<C>,
+    /// The members, as of the last update.
+    #[get = "pub"]
+    members: IndexSet<T>,
+    /// The changes that the last update reported.
+    #[get = "pub"]
+    changes: Vec<SetChange<T>>,
+}
+
+impl<T, C> SetOutput<T, C>
+where
+    T: Debug + Clone + Hash + Eq + 'static,
+    C: Debug + Clone + IntoIterator<Item = T> + 'static,
+{
+    pub(crate) fn new(source: TypedInputId<C>) -> SetOutput<T, C> {
+        SetOutput {
+            source: PrimitiveOutput::new(None, source),
+            members: IndexSet::new(),
+            changes: vec![],
+        }
+    }
+
+    pub fn contains(&self, member: &T) -> bool {
+        self.members.contains(member)
+    }
+
+    /// Returns true if the collection backing this output changed since the last time the output
+    /// was updated. This only validates revisions, and never computes the collection.
+    pub fn is_stale(&self, timeline: &RenderTransaction) -> bool {
+        self.source.is_stale(timeline)
+    }
+
+    /// Update the output if its collection is stale, and return whether any members were inserted
+    /// or removed. The changes themselves are available through `changes()` until the next update.
+    pub fn update(&mut self, timeline: &mut RenderTransaction) -> bool {
+        self.changes.clear();
+
+        if !self.source.update(timeline) {
+            return false;
+        }
+
+        let members: IndexSet<T> = self.source.value().into_iter().collect();
+
+        for member in &self.members {
+            if !members.contains(member) {
+                self.changes.push(SetChange::Remove {
+                    member: member.clone(),
+                });
+            }
+        }
+
+        for member in &members {
+            if !self.members.contains(member) {
+                self.changes.push(SetChange::Insert {
+                    member: member.clone(),
+                });
+            }
+        }
+
+        self.members = members;
+        !self.changes.is_empty()
+    }
+}
+
+impl<T, C> Output for SetOutput<T, C>
+where
+    T: Debug + Clone + Hash + Eq + 'static,
+    C: Debug + Clone + IntoIterator<Item = T> + 'static,
+{
+    fn is_stale(&self, timeline: &RenderTransaction) -> bool {
+        SetOutput::is_stale(self, timeline)
+    }
+
+    fn update(&mut self, timeline: &mut RenderTransaction) -> bool {
+        SetOutput::update(self, timeline)
+    }
+
+    fn dependencies(&self) -> Vec<DynId> {
+        self.source.dependencies()
+    }
+
+    fn explain(&self, timeline: &RenderTransaction) -> Vec<Explanation> {
+        self.source.explain(timeline)
+    }
+}
@@ -0,0 +1,141 @@
+use std::{any::Any, fmt::Debug};
+
+#[cfg(feature = "rayon")]
+use crate::timeline::ParRenderTransaction;
+use crate::timeline::{DynId, Explanation, Local, Mode, RenderTransaction, Shared, Storable};
+
+use super::Output;
+
+#[derive(Debug)]
+pub enum TreeChild<M: Mode = Local> {
+    Node(TreeOutput<M>),
+    Leaf(Box<M::Output>),
+}
+
+impl<M: Mode> TreeChild<M> {
+    pub fn node(node: TreeOutput<M>) -> TreeChild<M> {
+        TreeChild::Node(node)
+    }
+
+    pub fn leaf(leaf: impl Output + Storable<M>) -> TreeChild<M> {
+        TreeChild::Leaf(leaf.into_boxed_output())
+    }
+
+    fn output(&self) -> &dyn Output {
+        match self {
+            TreeChild::Node(node) => node,
+            TreeChild::Leaf(leaf) => M::as_output(leaf),
+        }
+    }
+
+    fn output_mut(&mut self) -> &mut dyn Output {
+        match self {
+            TreeChild::Node(node) => node,
+            TreeChild::Leaf(leaf) => M::as_output_mut(leaf),
+        }
+    }
+}
+
+/// A reactive tree node, with a fixed list of children. Each child is either another tree node or
+/// a leaf output (such as a `PrimitiveOutput` or `ListOutput`).
+///
+/// Updating a tree node updates every stale child, and leaves the rest of the tree alone.
+///
+/// The tree of a `Timeline<Shared>` is a `TreeOutput<Shared>`, whose leaves are `Send + Sync`.
+#[derive(Debug)]
+pub struct TreeOutput<M: Mode = Local> {
+    children: Vec<TreeChild<M>>,
+}
+
+impl TreeOutput {
+    pub fn new(children: Vec<TreeChild>) -> TreeOutput {
+        TreeOutput { children }
+    }
+}
+
+impl TreeOutput<Shared> {
+    pub fn shared(children: Vec<TreeChild<Shared>>) -> TreeOutput<Shared> {
+        TreeOutput { children }
+    }
+}
+
+impl<M: Mode> TreeOutput<M> {
+    pub fn children(&self) -> &[TreeChild<M>] {
+        &self.children
+    }
+
+    /// Find a descendant of this node. Each entry in `path` is the index of a child in its parent.
+    pub fn get(&self, path: &[usize]) -> Option<&TreeChild<M>> {
+        let (first, rest) = path.split_first()?;
+        let child = self.children.get(*first)?;
+
+        match (child, rest) {
+            (_, []) => Some(child),
+            (TreeChild::Node(node), rest) => node.get(rest),
+            (TreeChild::Leaf(_), _) => None,
+        }
+    }
+
+    /// Find a leaf output at `path`, if it exists and is an `O`.
+    pub fn leaf<O: Output>(&self, path: &[usize]) -> Option<&O> {
+        match self.get(path)? {
+            TreeChild::Leaf(leaf) => {
+                let leaf: &dyn Any = M::as_output(leaf);
+                leaf.downcast_ref()
+            }
+            TreeChild::Node(_) => None,
+        }
+    }
+}
+
+#[cfg(feature = "rayon")]
+impl TreeOutput<Shared> {
+    /// Like `update`, but updates the children on rayon's thread pool. Child nodes update their
+    /// own children in parallel as well.
+    pub fn par_update(&mut self, timeline: &ParRenderTransaction) -> bool {
+        use rayon::prelude::*;
+
+        self.children
+            .par_iter_mut()
+            .map_init(
+                || timeline.begin(),
+                |transaction, child| match child {
+                    TreeChild::Node(node) => node.par_update(timeline),
+                    TreeChild::Leaf(leaf) => leaf.update(transaction),
+                },
+            )
+            .reduce(|| false, |left, right| left || right)
+    }
+}
+
+impl<M: Mode> Output for TreeOutput<M> {
+    fn is_stale(&self, timeline: &RenderTransaction) -> bool {
+        self.children
+            .iter()
+            .any(|child| child.output().is_stale(timeline))
+    }
+
+    fn update(&mut self, timeline: &mut RenderTransaction) -> bool {
+        let mut updated = false;
+
+        for child in &mut self.children {
+            updated |= child.output_mut().update(timeline);
+        }
+
+        updated
+    }
+
+    fn dependencies(&self) -> Vec<DynId> {
+        self.children
+            .iter()
+            .flat_map(|child| child.output().dependencies())
+            .collect()
+    }
+
+    fn explain(&self, timeline: &RenderTransaction) -> Vec<Explanation> {
+        self.children
+            .iter()
+            .flat_map(|child| child.output().explain(timeline))
+            .collect()
+    }
+}
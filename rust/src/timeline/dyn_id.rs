@@ -1,6 +1,10 @@
-use std::{any::type_name, any::TypeId, fmt::Debug};
+use std::{
+    any::type_name,
+    any::TypeId,
+    fmt::{self, Debug, Display},
+};
 
-use crate::TypedInputId;
+use crate::{Error, TypedInputId};
 
 use super::id::{IdKind, InputId};
 
@@ -26,19 +30,45 @@ impl DynId {
         }
     }
 
+    pub fn kind(&self) -> IdKind {
+        self.kind
+    }
+
+    pub fn type_name(&self) -> &'static str {
+        self.type_name
+    }
+
+    pub(crate) fn type_id(&self) -> TypeId {
+        self.type_id
+    }
+
     #[doc(hidden)]
     pub fn downcast<T>(self) -> TypedInputId<T>
     where
-        T: Debug + Clone + 'static,
+        T: 'static,
+    {
+        self.try_downcast()
+            .unwrap_or_else(|error| panic!("{}", error))
+    }
+
+    #[doc(hidden)]
+    pub fn try_downcast<T>(self) -> Result<TypedInputId<T>, Error>
+    where
+        T: 'static,
     {
         if TypeId::of::<T>() == self.type_id {
-            TypedInputId::new(self.id, self.kind)
+            Ok(TypedInputId::new(self.id, self.kind))
         } else {
-            panic!(
-                "Can't downcast DynId of {} to {}",
-                self.type_name,
-                type_name::<T>()
-            )
+            Err(Error::TypeMismatch {
+                expected: type_name::<T>(),
+                actual: self.type_name,
+            })
         }
     }
 }
+
+impl Display for DynId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:?}<{}>({})", self.kind, self.type_name, self.id)
+    }
+}
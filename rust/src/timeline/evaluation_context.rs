@@ -1,44 +1,134 @@
 use std::fmt::Debug;
 
 use crate::{
-    inputs::{DerivedTag, ReactiveTag},
-    TypedInputId,
+    inputs::{DerivedTag, ReactiveTag, Refresh},
+    Error, TypedInputId,
 };
 
-use super::inputs::Inputs;
+use super::{DynId, Revision};
+
+use super::inputs::DynInputs;
 
 #[derive(Debug)]
 pub struct EvaluationContext<'a> {
-    stack: Vec<DerivedTag>,
-    pub(crate) inputs: &'a Inputs,
+    /// The derived computations that are currently being computed, innermost last.
+    stack: Vec<(DynId, DerivedTag)>,
+    pub(crate) inputs: &'a dyn DynInputs,
+    /// The past revision that inputs are read at, for a time-travel read. `None` reads the
+    /// current values.
+    as_of: Option<Revision>,
 }
 
 impl<'a> EvaluationContext<'a> {
-    pub(crate) fn new(inputs: &Inputs) -> EvaluationContext<'_> {
+    pub(crate) fn new(inputs: &dyn DynInputs) -> EvaluationContext<'_> {
+        EvaluationContext {
+            stack: vec![],
+            inputs,
+            as_of: None,
+        }
+    }
+
+    /// A context that reads every input as it was at `revision`.
+    pub(crate) fn at(inputs: &dyn DynInputs, revision: Revision) -> EvaluationContext<'_> {
         EvaluationContext {
             stack: vec![],
             inputs,
+            as_of: Some(revision),
         }
     }
 
-    pub(crate) fn push(&mut self, tag: DerivedTag) {
-        self.stack.push(tag);
+    pub(crate) fn as_of(&self) -> Option<Revision> {
+        self.as_of
+    }
+
+    pub(crate) fn push(&mut self, id: DynId, tag: DerivedTag) {
+        self.stack.push((id, tag));
     }
 
     pub(crate) fn pop(&mut self) -> DerivedTag {
-        let result = self.stack.pop().expect("popped a tag without pushing one");
-        result
+        let (_, tag) = self.stack.pop().expect("popped a tag without pushing one");
+        tag
+    }
+
+    /// Computing `id` is only allowed if it isn't already being computed further up the stack.
+    /// Otherwise, the computation would recurse forever, so report the path that led back to it.
+    pub(crate) fn check_cycle(&self, id: DynId) -> Result<(), Error> {
+        match self.stack.iter().position(|(entry, _)| *entry == id) {
+            None => Ok(()),
+            Some(start) => {
+                let mut path: Vec<DynId> = self.stack[start..].iter().map(|(id, _)| *id).collect();
+                path.push(id);
+                Err(Error::Cycle { path })
+            }
+        }
+    }
+
+    pub(crate) fn consume(&self, tag: ReactiveTag, id: DynId) {
+        let span = span!(TRACE, "read", id = %id, type_name = id.type_name());
+
+        span.in_scope(|| {
+            // constant tags never invalidate the current computation, so we don't need to validate
+            // them. This also means that a computation whose dependencies are all constant is
+            // itself constant. They're still recorded, so that the dependency graph shows them.
+            if tag.is_constant() {
+                self.consume_constant(id);
+                return;
+            }
+
+            if let Some((_, current)) = self.stack.last() {
+                current.add_dep(id, tag);
+            }
+        })
     }
 
-    pub(crate) fn consume(&self, tag: ReactiveTag) {
-        if let Some(current) = self.stack.last() {
-            current.add_dep(tag);
+    fn consume_constant(&self, id: DynId) {
+        if let Some((_, current)) = self.stack.last() {
+            current.add_constant(id);
         }
     }
 
+    /// Like `consume`, for a memo, which the current computation has to refresh before it can
+    /// trust its dependencies again.
+    pub(crate) fn consume_memo(&self, tag: ReactiveTag, id: DynId, refresh: Refresh) {
+        let span = span!(TRACE, "read", id = %id, type_name = id.type_name());
+
+        span.in_scope(|| {
+            if tag.is_constant() {
+                self.consume_constant(id);
+                return;
+            }
+
+            if let Some((_, current)) = self.stack.last() {
+                current.add_dep(id, tag);
+                current.add_memo(id, refresh);
+            }
+        })
+    }
+
     pub fn value<T>(&mut self, id: impl Into<TypedInputId<T>>) -> T
     where
-        T: Debug + Clone + 'static,
+        T: Clone + 'static,
+    {
+        self.try_value(id)
+            .unwrap_or_else(|error| panic!("{}", error))
+    }
+
+    /// Like `value`, but lends the value to `f` instead of cloning it, which avoids copying a large
+    /// cell, constant or list on every read.
+    pub fn with_value<T, U>(&mut self, id: impl Into<TypedInputId<T>>, f: impl FnOnce(&T) -> U) -> U
+    where
+        T: 'static,
+    {
+        self.inputs
+            .with_value(id, self, f)
+            .unwrap_or_else(|error| panic!("{}", error))
+    }
+
+    /// Like `value`, but returns an error instead of panicking if the input was removed, or if it
+    /// never belonged to this timeline.
+    pub fn try_value<T>(&mut self, id: impl Into<TypedInputId<T>>) -> Result<T, Error>
+    where
+        T: Clone + 'static,
     {
         self.inputs.value(id, self)
     }
@@ -0,0 +1,123 @@
+use std::fmt::{self, Display};
+
+use getset::Getters;
+
+use crate::inputs::ReactiveTag;
+
+use super::{DynId, Revision};
+
+/// A single question that validation asks: is `id` up to date for `since`?
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct Check {
+    pub id: DynId,
+    pub since: Revision,
+    /// The current revision of `id`.
+    pub revision: Revision,
+}
+
+impl Check {
+    pub fn is_up_to_date(&self) -> bool {
+        self.revision <= self.since
+    }
+}
+
+impl Display for Check {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Is {} up to date for {}? {}",
+            self.id,
+            self.since,
+            if self.is_up_to_date() { "yes" } else { "no" }
+        )
+    }
+}
+
+/// An input that changed, and the derived computations that the change propagated through.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Cause {
+    /// The cell, list or external storage that changed.
+    pub source: DynId,
+    /// The revision at which `source` changed.
+    pub revision: Revision,
+    /// Every input that the change flowed through, starting with `source` and ending with the
+    /// input that was explained.
+    pub path: Vec<DynId>,
+}
+
+/// Why an input changed since a revision, based on the dependencies that were recorded the last
+/// time each derived computation was computed.
+#[derive(Debug, Clone, Eq, PartialEq, Getters)]
+pub struct Explanation {
+    #[get = "pub"]
+    id: DynId,
+    #[get = "pub"]
+    since: Revision,
+    /// The checks that validating `id` performs, in order. The dependencies of a derived
+    /// computation are only checked if the derived computation itself isn't up to date.
+    #[get = "pub"]
+    checks: Vec<Check>,
+    /// The inputs that changed since `since`. Empty if `id` is up to date.
+    #[get = "pub"]
+    causes: Vec<Cause>,
+}
+
+impl Explanation {
+    pub(crate) fn new(id: DynId, tag: ReactiveTag, since: Revision) -> Explanation {
+        let mut explanation = Explanation {
+            id,
+            since,
+            checks: vec![],
+            causes: vec![],
+        };
+
+        explanation.walk(id, tag, &mut vec![]);
+        explanation
+    }
+
+    /// `path` holds the derived computations between the explained input and `id`, outermost
+    /// first.
+    fn walk(&mut self, id: DynId, tag: ReactiveTag, path: &mut Vec<DynId>) {
+        let check = Check {
+            id,
+            since: self.since,
+            revision: tag.revision(),
+        };
+        self.checks.push(check);
+
+        if check.is_up_to_date() {
+            return;
+        }
+
+        match tag {
+            ReactiveTag::Derived(derived) => {
+                path.push(id);
+                for (dependency, tag) in derived.dependencies() {
+                    self.walk(dependency, tag, path);
+                }
+                path.pop();
+            }
+            ReactiveTag::Tag(_) => self.causes.push(Cause {
+                source: id,
+                revision: check.revision,
+                path: std::iter::once(id)
+                    .chain(path.iter().rev().copied())
+                    .collect(),
+            }),
+        }
+    }
+
+    pub fn is_stale(&self) -> bool {
+        !self.causes.is_empty()
+    }
+}
+
+impl Display for Explanation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for check in &self.checks {
+            writeln!(f, "{}", check)?;
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,194 @@
+use std::{
+    collections::HashSet,
+    fmt::{self, Display, Write},
+};
+
+use getset::Getters;
+
+use super::{DynId, IdKind};
+
+/// A node in a `DependencyGraph`.
+#[derive(Debug, Copy, Clone, Hash, Eq, PartialEq)]
+pub enum GraphNode {
+    Input(DynId),
+    /// An output that was registered with the timeline's program. `index` is the position of the
+    /// output in the program.
+    Output {
+        index: usize,
+        type_name: &'static str,
+    },
+}
+
+impl GraphNode {
+    pub fn type_name(&self) -> &'static str {
+        match self {
+            GraphNode::Input(id) => id.type_name(),
+            GraphNode::Output { type_name, .. } => type_name,
+        }
+    }
+
+    /// `CellId`, `DerivedId` and so on for inputs, and `Output` for outputs.
+    pub fn kind(&self) -> String {
+        match self {
+            GraphNode::Input(id) => format!("{:?}", id.kind()),
+            GraphNode::Output { .. } => "Output".to_string(),
+        }
+    }
+
+    fn shape(&self) -> &'static str {
+        match self {
+            GraphNode::Input(id) if id.kind() == IdKind::DerivedId => "ellipse",
+            GraphNode::Input(_) => "box",
+            GraphNode::Output { .. } => "note",
+        }
+    }
+}
+
+impl Display for GraphNode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            GraphNode::Input(id) => write!(f, "{}", id),
+            GraphNode::Output { index, type_name } => write!(f, "Output<{}>({})", type_name, index),
+        }
+    }
+}
+
+/// `to` consumed `from` the last time that `to` was computed or updated, so the data flows from
+/// `from` to `to`.
+#[derive(Debug, Copy, Clone, Hash, Eq, PartialEq)]
+pub struct GraphEdge {
+    pub from: GraphNode,
+    pub to: GraphNode,
+}
+
+/// The inputs of a timeline and the outputs in its program, with an edge for every dependency that
+/// was recorded by the last evaluation.
+///
+/// Constants are never validated, but reading one is still recorded, so it has an edge to every
+/// derived computation that read it. An input that was never computed doesn't have any incoming
+/// edges yet.
+#[derive(Debug, Clone, Default, Getters)]
+pub struct DependencyGraph {
+    #[get = "pub"]
+    nodes: Vec<GraphNode>,
+    #[get = "pub"]
+    edges: Vec<GraphEdge>,
+    /// The same edges as `edges`, so that adding an edge doesn't have to scan all of them.
+    seen: HashSet<GraphEdge>,
+}
+
+impl DependencyGraph {
+    pub(crate) fn add_node(&mut self, node: GraphNode) {
+        self.nodes.push(node);
+    }
+
+    pub(crate) fn add_edge(&mut self, from: GraphNode, to: GraphNode) {
+        let edge = GraphEdge { from, to };
+
+        // a computation that reads the same input twice only depends on it once
+        if self.seen.insert(edge) {
+            self.edges.push(edge);
+        }
+    }
+
+    /// Drop the edges from inputs that were deleted since they were consumed.
+    pub(crate) fn finish(mut self) -> DependencyGraph {
+        let nodes: HashSet<GraphNode> = self.nodes.iter().copied().collect();
+        let live = |edge: &GraphEdge| nodes.contains(&edge.from) && nodes.contains(&edge.to);
+        self.edges.retain(live);
+        self.seen.retain(live);
+        self
+    }
+
+    /// The incoming edges of `node`.
+    pub fn dependencies(&self, node: GraphNode) -> impl Iterator<Item = GraphNode> + '_ {
+        self.edges
+            .iter()
+            .filter(move |edge| edge.to == node)
+            .map(|edge| edge.from)
+    }
+
+    /// Render the graph in Graphviz's DOT language.
+    pub fn to_dot(&self) -> String {
+        let mut dot = String::from("digraph dependencies {\n");
+
+        for node in &self.nodes {
+            writeln!(
+                dot,
+                "    {} [shape={}];",
+                quote(&node.to_string()),
+                node.shape()
+            )
+            .unwrap();
+        }
+
+        for edge in &self.edges {
+            writeln!(
+                dot,
+                "    {} -> {};",
+                quote(&edge.from.to_string()),
+                quote(&edge.to.to_string())
+            )
+            .unwrap();
+        }
+
+        dot.push_str("}\n");
+        dot
+    }
+
+    /// Render the graph as JSON, with a `nodes` array and an `edges` array. An edge refers to
+    /// nodes by their `id`.
+    pub fn to_json(&self) -> String {
+        let nodes: Vec<String> = self
+            .nodes
+            .iter()
+            .map(|node| {
+                format!(
+                    "{{\"id\":{},\"kind\":{},\"type_name\":{}}}",
+                    quote(&node.to_string()),
+                    quote(&node.kind()),
+                    quote(node.type_name())
+                )
+            })
+            .collect();
+
+        let edges: Vec<String> = self
+            .edges
+            .iter()
+            .map(|edge| {
+                format!(
+                    "{{\"from\":{},\"to\":{}}}",
+                    quote(&edge.from.to_string()),
+                    quote(&edge.to.to_string())
+                )
+            })
+            .collect();
+
+        format!(
+            "{{\"nodes\":[{}],\"edges\":[{}]}}",
+            nodes.join(","),
+            edges.join(",")
+        )
+    }
+}
+
+/// A double-quoted string, which both DOT and JSON accept. JSON doesn't allow any control
+/// character inside of a string, so they're all escaped.
+fn quote(string: &str) -> String {
+    let mut quoted = String::from("\"");
+
+    for c in string.chars() {
+        match c {
+            '"' => quoted.push_str("\\\""),
+            '\\' => quoted.push_str("\\\\"),
+            '\n' => quoted.push_str("\\n"),
+            '\r' => quoted.push_str("\\r"),
+            '\t' => quoted.push_str("\\t"),
+            c if c < '\u{20}' => write!(quoted, "\\u{:04x}", c as u32).unwrap(),
+            c => quoted.push(c),
+        }
+    }
+
+    quoted.push('"');
+    quoted
+}
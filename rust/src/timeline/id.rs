@@ -1,9 +1,16 @@
 use getset::Getters;
-use std::{fmt::Debug, hash::Hash, marker::PhantomData};
+use std::{
+    any::type_name,
+    fmt::{self, Debug, Display},
+    hash::Hash,
+    marker::PhantomData,
+};
+
+use crate::Error;
 
 use super::{dyn_id::DynId, EvaluationContext};
 
-#[derive(Debug, Copy, Clone, Hash, Eq, PartialEq)]
+#[derive(Debug, Copy, Clone, Hash, Eq, PartialEq, Ord, PartialOrd)]
 pub struct InputId {
     id: u64,
 }
@@ -19,7 +26,7 @@ impl InputId {
 
     pub(crate) fn typed<T, K>(self, kind: fn() -> K) -> TypedInputIdWithKind<T, K>
     where
-        T: Clone + Debug + 'static,
+        T: 'static,
         K: IdKindFor<T>,
     {
         TypedInputIdWithKind {
@@ -30,39 +37,65 @@ impl InputId {
     }
 }
 
+impl Display for InputId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.id)
+    }
+}
+
+#[allow(clippy::enum_variant_names)]
 #[derive(Copy, Clone, Debug, Hash, Eq, PartialEq)]
 pub enum IdKind {
     CellId,
+    ConstantId,
     DerivedId,
     ListId,
+    ExternalId,
 }
 
 pub trait IdKindFor<T>: Copy
 where
-    T: Debug + Clone + 'static,
+    T: 'static,
 {
     fn id_kind(self) -> IdKind;
 }
 
 pub trait ComputeKindFor<T>: IdKindFor<T>
 where
-    T: Debug + Clone + 'static,
+    T: 'static,
 {
 }
 
 macro_rules! id_kind {
     (cell: $id:ident) => {
-        #[derive(Debug, Clone)]
-        pub struct $id<T: Debug + Clone + 'static> {
+        pub struct $id<T: 'static> {
             marker: PhantomData<T>,
         }
 
-        impl<T> Copy for $id<T> where T: Debug + Clone + 'static {}
+        impl<T> Copy for $id<T> where T: 'static {}
+
+        impl<T> Clone for $id<T>
+        where
+            T: 'static,
+        {
+            fn clone(&self) -> Self {
+                *self
+            }
+        }
+
+        impl<T> Debug for $id<T>
+        where
+            T: 'static,
+        {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                write!(f, "{}<{}>", stringify!($id), type_name::<T>())
+            }
+        }
 
         #[allow(non_snake_case)]
         pub(crate) fn $id<T>() -> $id<T>
         where
-            T: Debug + Clone + 'static,
+            T: 'static,
         {
             $id {
                 marker: PhantomData,
@@ -71,7 +104,7 @@ macro_rules! id_kind {
 
         impl<T> IdKindFor<T> for $id<T>
         where
-            T: Clone + Debug + 'static,
+            T: 'static,
         {
             fn id_kind(self) -> IdKind {
                 IdKind::$id
@@ -82,23 +115,20 @@ macro_rules! id_kind {
     (compute: $id:ident) => {
         id_kind!(cell: $id);
 
-        impl<T> ComputeKindFor<T> for $id<T> where T: Clone + Debug + 'static {}
+        impl<T> ComputeKindFor<T> for $id<T> where T: 'static {}
     };
 }
 
 id_kind!(cell: CellId);
+id_kind!(cell: ConstantId);
 id_kind!(compute: DerivedId);
-// id_kind!(ListId, |id, inputs| {
-//     let map = inputs.read_map_for::<T>();
-//     let cell = map.get_list(id);
-//     cell.read()
-// });
+id_kind!(cell: ListId);
+id_kind!(cell: ExternalId);
 
-#[derive(Debug)]
 pub struct TypedInputIdWithKind<T, K>
 where
     K: IdKindFor<T>,
-    T: Clone + Debug + 'static,
+    T: 'static,
 {
     id: InputId,
     marker: PhantomData<T>,
@@ -107,7 +137,7 @@ where
 
 impl<T, K> TypedInputIdWithKind<T, K>
 where
-    T: Clone + Debug + 'static,
+    T: 'static,
     K: IdKindFor<T>,
 {
     pub(crate) fn to_dyn(self) -> DynId {
@@ -122,14 +152,17 @@ where
         self.id
     }
 
-    pub fn value<'a>(self, ctx: &mut EvaluationContext) -> T {
+    pub fn value(self, ctx: &mut EvaluationContext) -> T
+    where
+        T: Clone,
+    {
         ctx.value(self)
     }
 }
 
 impl<T, K> From<TypedInputIdWithKind<T, K>> for TypedInputId<T>
 where
-    T: Clone + Debug + 'static,
+    T: 'static,
     K: IdKindFor<T>,
 {
     fn from(input: TypedInputIdWithKind<T, K>) -> TypedInputId<T> {
@@ -144,7 +177,7 @@ where
 impl<T, K> TypedInputIdWithKind<T, K>
 where
     K: IdKindFor<T>,
-    T: Clone + Debug + 'static,
+    T: 'static,
 {
     pub(crate) fn new(id: InputId, kind: K) -> TypedInputIdWithKind<T, K> {
         TypedInputIdWithKind {
@@ -158,7 +191,7 @@ where
 impl<T, K> TypedInputIdWithKind<T, K>
 where
     K: IdKindFor<T>,
-    T: Clone + Debug + 'static,
+    T: 'static,
 {
     pub(crate) fn next(self) -> TypedInputIdWithKind<T, K> {
         TypedInputIdWithKind {
@@ -169,7 +202,7 @@ where
     }
 }
 
-#[derive(Debug, Clone, Hash, Eq, PartialEq, Getters)]
+#[derive(Getters)]
 pub struct TypedInputId<T> {
     id: InputId,
     marker: PhantomData<T>,
@@ -179,7 +212,7 @@ pub struct TypedInputId<T> {
 
 impl<T> TypedInputId<T>
 where
-    T: Debug + Clone + 'static,
+    T: 'static,
 {
     pub(crate) fn new(id: InputId, kind: IdKind) -> TypedInputId<T> {
         TypedInputId {
@@ -198,38 +231,94 @@ where
     }
 
     pub(crate) fn downcast<K: IdKindFor<T>>(self, kind: fn() -> K) -> TypedInputIdWithKind<T, K> {
+        self.try_downcast(kind)
+            .unwrap_or_else(|error| panic!("{}", error))
+    }
+
+    pub(crate) fn try_downcast<K: IdKindFor<T>>(
+        self,
+        kind: fn() -> K,
+    ) -> Result<TypedInputIdWithKind<T, K>, Error> {
         let kind = kind();
 
-        assert_eq!(
-            self.kind,
-            kind.id_kind(),
-            "attempted to downcast a {:?} into a {:?}",
-            self.kind,
-            kind.id_kind()
-        );
+        if self.kind != kind.id_kind() {
+            return Err(Error::KindMismatch {
+                expected: kind.id_kind(),
+                actual: self.kind,
+            });
+        }
 
-        TypedInputIdWithKind {
+        Ok(TypedInputIdWithKind {
             id: self.id,
             marker: PhantomData,
             kind,
-        }
+        })
     }
 }
 
 impl<T> From<TypedInputId<T>> for DynId
 where
-    T: Clone + Debug + 'static,
+    T: 'static,
 {
     fn from(input: TypedInputId<T>) -> Self {
         DynId::new::<T>(input.id, input.kind)
     }
 }
 
-impl<T> Copy for TypedInputId<T> where T: Clone + Debug + 'static {}
+impl<T, K> From<TypedInputIdWithKind<T, K>> for DynId
+where
+    T: 'static,
+    K: IdKindFor<T>,
+{
+    fn from(input: TypedInputIdWithKind<T, K>) -> Self {
+        input.to_dyn()
+    }
+}
+
+impl<T> Copy for TypedInputId<T> where T: 'static {}
+
+impl<T> Clone for TypedInputId<T>
+where
+    T: 'static,
+{
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T> Hash for TypedInputId<T>
+where
+    T: 'static,
+{
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        self.id.hash(state);
+        self.kind.hash(state);
+    }
+}
+
+impl<T> PartialEq for TypedInputId<T>
+where
+    T: 'static,
+{
+    fn eq(&self, other: &Self) -> bool {
+        self.id == other.id && self.kind == other.kind
+    }
+}
+
+impl<T> Eq for TypedInputId<T> where T: 'static {}
+
+impl<T> Debug for TypedInputId<T>
+where
+    T: 'static,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:?}<{}>({})", self.kind, type_name::<T>(), self.id)
+    }
+}
 
 impl<T> TypedInputId<T>
 where
-    T: Clone + Debug + 'static,
+    T: 'static,
 {
     // pub(crate) fn value(&self, inputs: &Inputs) -> T {
     //     match self.kind {
@@ -257,7 +346,7 @@ where
 impl<T, K> Hash for TypedInputIdWithKind<T, K>
 where
     K: IdKindFor<T>,
-    T: Clone + Debug + 'static,
+    T: 'static,
 {
     fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
         self.kind.id_kind().hash(state);
@@ -268,7 +357,7 @@ where
 impl<T, K> PartialEq for TypedInputIdWithKind<T, K>
 where
     K: IdKindFor<T>,
-    T: Clone + Debug + 'static,
+    T: 'static,
 {
     fn eq(&self, other: &Self) -> bool {
         self.id == other.id
@@ -278,23 +367,33 @@ where
 impl<T, K> Eq for TypedInputIdWithKind<T, K>
 where
     K: IdKindFor<T>,
-    T: Clone + Debug + 'static,
+    T: 'static,
 {
 }
 
 impl<T, K> Copy for TypedInputIdWithKind<T, K>
 where
     K: IdKindFor<T>,
-    T: Clone + Debug + 'static,
+    T: 'static,
 {
 }
 
 impl<T, K> Clone for TypedInputIdWithKind<T, K>
 where
     K: IdKindFor<T>,
-    T: Clone + Debug + 'static,
+    T: 'static,
 {
     fn clone(&self) -> Self {
         *self
     }
 }
+
+impl<T, K> Debug for TypedInputIdWithKind<T, K>
+where
+    K: IdKindFor<T>,
+    T: 'static,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:?}<{}>({})", self.kind(), type_name::<T>(), self.id)
+    }
+}
@@ -1,23 +1,35 @@
-use std::{any::type_name, fmt::Debug};
+use std::{
+    any::{type_name, Any, TypeId},
+    fmt::Debug,
+};
 
-use fxtypemap::TypeMap;
 use indexmap::IndexMap;
 
-use crate::TypedInputId;
 use crate::{
-    inputs::{Reactive, ReactiveCell, ReactiveDerived},
+    inputs::{
+        DerivedTag, Reactive, ReactiveCell, ReactiveDerived, ReactiveExternal, ReactiveList,
+        ReactiveTag,
+    },
     Revision,
 };
+use crate::{Error, TypedInputId};
 
 use super::{
-    id::{CellId, DerivedId, IdKind, IdKindFor, InputId, TypedInputIdWithKind},
-    EvaluationContext,
+    graph::{DependencyGraph, GraphNode},
+    id::{
+        CellId, ConstantId, DerivedId, ExternalId, IdKind, IdKindFor, InputId, ListId,
+        TypedInputIdWithKind,
+    },
+    DynId, EvaluationContext, Explanation, Mode, Storable,
 };
 
+#[cfg(feature = "serde")]
+use super::snapshot::Persisted;
+
 #[derive(Debug, Clone)]
 pub(super) struct InternalTypedInputs<T, Id, R>
 where
-    T: Debug + Clone + 'static,
+    T: 'static,
     Id: IdKindFor<T>,
     R: Reactive,
 {
@@ -27,7 +39,7 @@ where
 
 impl<T, Id, R> InternalTypedInputs<T, Id, R>
 where
-    T: Debug + Clone + 'static,
+    T: 'static,
     Id: IdKindFor<T>,
     R: Reactive,
 {
@@ -50,6 +62,11 @@ where
         next
     }
 
+    /// Put back a value that was removed from the map.
+    fn restore(&mut self, key: TypedInputIdWithKind<T, Id>, value: R) {
+        self.map.insert(key.as_unchecked_id(), value);
+    }
+
     fn get_mut(&mut self, key: TypedInputIdWithKind<T, Id>) -> Option<&mut R> {
         self.map.get_mut(&key.as_unchecked_id())
     }
@@ -57,21 +74,101 @@ where
     fn get(&self, key: TypedInputIdWithKind<T, Id>) -> Option<&R> {
         self.map.get(&key.as_unchecked_id())
     }
+
+    fn lookup(&self, key: TypedInputIdWithKind<T, Id>) -> Result<&R, Error> {
+        match self.get(key) {
+            Some(reactive) => Ok(reactive),
+            None => Err(self.missing(key)),
+        }
+    }
+
+    fn lookup_mut(&mut self, key: TypedInputIdWithKind<T, Id>) -> Result<&mut R, Error> {
+        if !self.contains(key) {
+            return Err(self.missing(key));
+        }
+
+        Ok(self.get_mut(key).unwrap())
+    }
+
+    fn contains(&self, key: TypedInputIdWithKind<T, Id>) -> bool {
+        self.map.contains_key(&key.as_unchecked_id())
+    }
+
+    fn remove(&mut self, key: TypedInputIdWithKind<T, Id>) -> Option<R> {
+        self.map.shift_remove(&key.as_unchecked_id())
+    }
+
+    /// Ids are never reused, so an id that was handed out but is no longer in the map must have
+    /// been removed.
+    fn is_removed(&self, key: TypedInputIdWithKind<T, Id>) -> bool {
+        !self.contains(key) && key.as_unchecked_id() < self.next_id.as_unchecked_id()
+    }
+
+    /// The inputs that weren't removed, in the order that they were added.
+    fn entries(&self) -> impl Iterator<Item = (DynId, &R)> + '_ {
+        let kind = self.next_id.kind();
+        self.map
+            .iter()
+            .map(move |(id, reactive)| (DynId::new::<T>(*id, kind), reactive))
+    }
+
+    fn missing(&self, key: TypedInputIdWithKind<T, Id>) -> Error {
+        if self.is_removed(key) {
+            Error::Removed { id: key.to_dyn() }
+        } else {
+            Error::UnknownId { id: key.to_dyn() }
+        }
+    }
 }
 
+/// Puts a removed input back.
+type Restore<T, M> = Box<dyn FnOnce(&mut TypedInputs<T, M>)>;
+
 #[derive(Debug)]
-pub(crate) struct TypedInputs<T: Debug + Clone + 'static> {
+pub(crate) struct TypedInputs<T: 'static, M: Mode> {
     pub(super) cells: InternalTypedInputs<T, CellId<T>, ReactiveCell<T>>,
-    pub(super) derived: InternalTypedInputs<T, DerivedId<T>, ReactiveDerived<T>>,
+    pub(super) constants: InternalTypedInputs<T, ConstantId<T>, ReactiveCell<T>>,
+    pub(super) derived: InternalTypedInputs<T, DerivedId<T>, ReactiveDerived<T, M>>,
+    // only populated when `T` is a `ReactiveList`
+    pub(super) lists: InternalTypedInputs<T, ListId<T>, ReactiveCell<T>>,
+    pub(super) externals: InternalTypedInputs<T, ExternalId<T>, ReactiveExternal<T, M>>,
 }
 
-impl<T: Debug + Clone + 'static> TypedInputs<T> {
+impl<T: 'static, M: Mode> TypedInputs<T, M> {
     // pub(crate) fn consume()
 
-    pub(crate) fn for_type() -> TypedInputs<T> {
-        TypedInputs::<T> {
+    pub(crate) fn for_type() -> TypedInputs<T, M> {
+        TypedInputs::<T, M> {
             cells: InternalTypedInputs::new(CellId),
+            constants: InternalTypedInputs::new(ConstantId),
             derived: InternalTypedInputs::new(DerivedId),
+            lists: InternalTypedInputs::new(ListId),
+            externals: InternalTypedInputs::new(ExternalId),
+        }
+    }
+
+    fn add_to_graph(&self, graph: &mut DependencyGraph) {
+        let cells = self.cells.entries().map(|(id, _)| id);
+        let constants = self.constants.entries().map(|(id, _)| id);
+        let lists = self.lists.entries().map(|(id, _)| id);
+        let externals = self.externals.entries().map(|(id, _)| id);
+
+        for id in cells.chain(constants).chain(lists).chain(externals) {
+            graph.add_node(GraphNode::Input(id));
+        }
+
+        for (id, derived) in self.derived.entries() {
+            graph.add_node(GraphNode::Input(id));
+
+            let tag = derived.derived_tag();
+            let dependencies = tag
+                .dependencies()
+                .into_iter()
+                .map(|(dependency, _)| dependency);
+
+            for dependency in dependencies.chain(tag.constants()) {
+                graph.add_edge(GraphNode::Input(dependency), GraphNode::Input(id));
+            }
         }
     }
 
@@ -82,38 +179,179 @@ impl<T: Debug + Clone + 'static> TypedInputs<T> {
         self.cells.insert(value)
     }
 
+    pub(crate) fn add_constant(
+        &mut self,
+        value: ReactiveCell<T>,
+    ) -> TypedInputIdWithKind<T, ConstantId<T>> {
+        self.constants.insert(value)
+    }
+
+    pub(crate) fn add_external(
+        &mut self,
+        value: ReactiveExternal<T, M>,
+    ) -> TypedInputIdWithKind<T, ExternalId<T>> {
+        self.externals.insert(value)
+    }
+
     pub(crate) fn add_derived(
         &mut self,
-        value: ReactiveDerived<T>,
+        value: ReactiveDerived<T, M>,
     ) -> TypedInputIdWithKind<T, DerivedId<T>> {
         self.derived.insert(value)
     }
 
-    fn revision(&self, id: TypedInputId<T>) -> Option<Revision> {
+    pub(crate) fn add_list(
+        &mut self,
+        value: ReactiveCell<T>,
+    ) -> TypedInputIdWithKind<T, ListId<T>> {
+        self.lists.insert(value)
+    }
+
+    fn tag(&self, id: TypedInputId<T>) -> Result<ReactiveTag, Error> {
         match id.kind() {
-            IdKind::CellId => Some(
-                self.cells
-                    .get(id.downcast(CellId))
-                    .expect("typed cell didn't exist")
-                    .get_tag()
-                    .revision(),
-            ),
-            IdKind::DerivedId => Some(
-                self.derived
-                    .get(id.downcast(DerivedId))
-                    .expect("typed derived didn't exist")
-                    .get_tag()
-                    .revision(),
-            ),
-            IdKind::ListId => unimplemented!("TypedInputs::revision for list"),
+            IdKind::CellId => Ok(self.cells.lookup(id.try_downcast(CellId)?)?.get_tag()),
+            IdKind::ConstantId => Ok(self
+                .constants
+                .lookup(id.try_downcast(ConstantId)?)?
+                .get_tag()),
+            IdKind::DerivedId => Ok(self.derived.lookup(id.try_downcast(DerivedId)?)?.get_tag()),
+            IdKind::ListId => Ok(self.lists.lookup(id.try_downcast(ListId)?)?.get_tag()),
+            IdKind::ExternalId => Ok(self
+                .externals
+                .lookup(id.try_downcast(ExternalId)?)?
+                .get_tag()),
+        }
+    }
+
+    fn revision(&self, id: TypedInputId<T>) -> Result<Revision, Error> {
+        match id.kind() {
+            IdKind::CellId => Ok(self
+                .cells
+                .lookup(id.try_downcast(CellId)?)?
+                .get_tag()
+                .revision()),
+            IdKind::ConstantId => {
+                self.constants.lookup(id.try_downcast(ConstantId)?)?;
+                Ok(Revision::constant())
+            }
+            IdKind::DerivedId => Ok(self
+                .derived
+                .lookup(id.try_downcast(DerivedId)?)?
+                .get_tag()
+                .revision()),
+            IdKind::ListId => Ok(self
+                .lists
+                .lookup(id.try_downcast(ListId)?)?
+                .get_tag()
+                .revision()),
+            IdKind::ExternalId => Ok(self
+                .externals
+                .lookup(id.try_downcast(ExternalId)?)?
+                .revision()),
+        }
+    }
+
+    pub(crate) fn value(&self, id: TypedInputId<T>, ctx: &mut EvaluationContext) -> Result<T, Error>
+    where
+        T: Clone,
+    {
+        if let Some(revision) = ctx.as_of() {
+            return self.with_value_at(id, revision, ctx, T::clone);
+        }
+
+        match id.kind() {
+            IdKind::CellId => self.read_cell(id.try_downcast(CellId)?, ctx),
+            IdKind::ConstantId => self.read_constant(id.try_downcast(ConstantId)?, ctx),
+            IdKind::DerivedId => self.compute_derived(id.try_downcast(DerivedId)?, ctx),
+            IdKind::ListId => self.read_list(id.try_downcast(ListId)?, ctx),
+            IdKind::ExternalId => self.read_external(id.try_downcast(ExternalId)?, ctx),
+        }
+    }
+
+    /// Like `value`, but lends the value to `f` instead of returning it. Cells, constants and lists
+    /// are lent without being cloned.
+    pub(crate) fn with_value<U>(
+        &self,
+        id: TypedInputId<T>,
+        ctx: &mut EvaluationContext,
+        f: impl FnOnce(&T) -> U,
+    ) -> Result<U, Error> {
+        if let Some(revision) = ctx.as_of() {
+            return self.with_value_at(id, revision, ctx, f);
+        }
+
+        match id.kind() {
+            IdKind::CellId => {
+                let id = id.try_downcast(CellId)?;
+                let cell = self.cells.lookup(id)?;
+                ctx.consume(cell.get_tag(), id.to_dyn());
+                Ok(f(cell.borrow()))
+            }
+            IdKind::ConstantId => {
+                let id = id.try_downcast(ConstantId)?;
+                let constant = self.constants.lookup(id)?;
+                ctx.consume(constant.get_tag(), id.to_dyn());
+                Ok(f(constant.borrow()))
+            }
+            IdKind::ListId => {
+                let id = id.try_downcast(ListId)?;
+                let list = self.lists.lookup(id)?;
+                ctx.consume(list.get_tag(), id.to_dyn());
+                Ok(f(list.borrow()))
+            }
+            IdKind::DerivedId => Ok(f(&self.compute_derived(id.try_downcast(DerivedId)?, ctx)?)),
+            IdKind::ExternalId => Ok(f(&self.read_external(id.try_downcast(ExternalId)?, ctx)?)),
         }
     }
 
-    pub(crate) fn value(&self, id: TypedInputId<T>, ctx: &mut EvaluationContext) -> T {
+    /// Like `with_value`, but as of a past revision. Derived computations are computed from
+    /// scratch, and none of the reads are tracked, so the present state of the timeline is left
+    /// alone.
+    fn with_value_at<U>(
+        &self,
+        id: TypedInputId<T>,
+        revision: Revision,
+        ctx: &mut EvaluationContext,
+        f: impl FnOnce(&T) -> U,
+    ) -> Result<U, Error> {
+        let unavailable = || Error::HistoryUnavailable {
+            id: id.into(),
+            revision,
+        };
+
         match id.kind() {
-            IdKind::CellId => self.read_cell(id.downcast(CellId), ctx),
-            IdKind::DerivedId => self.compute_derived(id.downcast(DerivedId), ctx),
-            IdKind::ListId => unimplemented!("Inputs::get_value for lists"),
+            IdKind::CellId => {
+                let cell = self.cells.lookup(id.try_downcast(CellId)?)?;
+                Ok(f(cell.borrow_at(revision).ok_or_else(unavailable)?))
+            }
+            IdKind::ConstantId => {
+                let constant = self.constants.lookup(id.try_downcast(ConstantId)?)?;
+                Ok(f(constant.borrow()))
+            }
+            IdKind::ListId => {
+                let list = self.lists.lookup(id.try_downcast(ListId)?)?;
+                Ok(f(list.borrow_at(revision).ok_or_else(unavailable)?))
+            }
+            IdKind::ExternalId => {
+                let external = self.externals.lookup(id.try_downcast(ExternalId)?)?;
+
+                if external.revision() > revision {
+                    return Err(unavailable());
+                }
+
+                Ok(f(&external.read()))
+            }
+            IdKind::DerivedId => {
+                let id = id.try_downcast(DerivedId)?;
+                let derived = self.derived.lookup(id)?;
+                ctx.check_cycle(id.to_dyn())?;
+
+                ctx.push(id.to_dyn(), DerivedTag::default());
+                let value = derived.compute(ctx);
+                ctx.pop();
+
+                Ok(f(&value))
+            }
         }
     }
 
@@ -121,27 +359,106 @@ impl<T: Debug + Clone + 'static> TypedInputs<T> {
         &self,
         id: TypedInputIdWithKind<T, CellId<T>>,
         stack: &mut EvaluationContext,
-    ) -> T {
-        let cell = self.cells.get(id).expect("typed cell didn't exist");
-        stack.consume(cell.get_tag());
-        cell.read()
+    ) -> Result<T, Error>
+    where
+        T: Clone,
+    {
+        let cell = self.cells.lookup(id)?;
+        stack.consume(cell.get_tag(), id.to_dyn());
+        Ok(cell.read())
+    }
+
+    fn read_list(
+        &self,
+        id: TypedInputIdWithKind<T, ListId<T>>,
+        ctx: &mut EvaluationContext,
+    ) -> Result<T, Error>
+    where
+        T: Clone,
+    {
+        let list = self.lists.lookup(id)?;
+        ctx.consume(list.get_tag(), id.to_dyn());
+        Ok(list.read())
+    }
+
+    fn read_external(
+        &self,
+        id: TypedInputIdWithKind<T, ExternalId<T>>,
+        ctx: &mut EvaluationContext,
+    ) -> Result<T, Error> {
+        let external = self.externals.lookup(id)?;
+        ctx.consume(external.get_tag(), id.to_dyn());
+        Ok(external.read())
+    }
+
+    fn read_constant(
+        &self,
+        id: TypedInputIdWithKind<T, ConstantId<T>>,
+        ctx: &mut EvaluationContext,
+    ) -> Result<T, Error>
+    where
+        T: Clone,
+    {
+        // constants never change, so the read is only recorded for the dependency graph
+        let constant = self.constants.lookup(id)?;
+        ctx.consume(constant.get_tag(), id.to_dyn());
+        Ok(constant.read())
     }
 
     fn compute_derived(
         &self,
         id: TypedInputIdWithKind<T, DerivedId<T>>,
         ctx: &mut EvaluationContext,
-    ) -> T {
-        let cell = self.derived.get(id).expect("typed derived didn't exist");
-        cell.reset_tag(|derived| {
-            ctx.push(derived);
+    ) -> Result<T, Error> {
+        let span = span!(TRACE, "compute_derived", id = %id.to_dyn(), type_name = type_name::<T>());
+        let value = span.in_scope(|| self.refresh_derived(id, ctx))?;
+        let cell = self.derived.lookup(id)?;
 
-            let result = cell.compute(ctx);
+        if cell.is_memo() {
+            ctx.consume_memo(cell.get_tag(), id.to_dyn(), refresh_memo);
+        } else {
+            ctx.consume(cell.get_tag(), id.to_dyn());
+        }
+
+        Ok(value)
+    }
+
+    /// Bring a derived computation up to date and return its value, without tracking it as a
+    /// dependency of the current computation.
+    fn refresh_derived(
+        &self,
+        id: TypedInputIdWithKind<T, DerivedId<T>>,
+        ctx: &mut EvaluationContext,
+    ) -> Result<T, Error> {
+        let cell = self.derived.lookup(id)?;
+        ctx.check_cycle(id.to_dyn())?;
 
-            let tag = ctx.pop();
-            ctx.consume(tag.into());
+        if let Some(value) = cell.memoized() {
+            return Ok(value);
+        }
+
+        if cell.is_memo() {
+            // a memo that this memo read may recompute to the same value as before, in which case
+            // it's backdated, and this memo is still valid
+            for (memo, refresh) in cell.derived_tag().memos() {
+                // if the memo can't be refreshed, recomputing this memo reports the problem
+                let _ = refresh(ctx, memo);
+            }
+
+            if let Some(value) = cell.memoized() {
+                return Ok(value);
+            }
+        }
+
+        let result = cell.reset_tag(|derived| {
+            ctx.push(id.to_dyn(), derived);
+            let result = cell.compute(ctx);
+            ctx.pop();
             result
-        })
+        });
+
+        cell.memoize(&result);
+        Ok(result)
     }
 
     pub(crate) fn update_cell(
@@ -149,43 +466,440 @@ impl<T: Debug + Clone + 'static> TypedInputs<T> {
         id: TypedInputIdWithKind<T, CellId<T>>,
         value: T,
         revision: Revision,
-    ) {
-        let cell = self
-            .cells
-            .get_mut(id.into())
-            .expect("typed cell didn't exist");
-        cell.update(value, revision);
+    ) -> Result<(T, Revision), Error> {
+        let cell = self.cells.lookup_mut(id)?;
+        Ok(cell.update(value, revision))
+    }
+
+    fn restore_cell(
+        &mut self,
+        id: TypedInputIdWithKind<T, CellId<T>>,
+        value: T,
+        revision: Revision,
+    ) -> Result<(), Error> {
+        self.cells.lookup_mut(id)?.restore(value, revision);
+        Ok(())
+    }
+
+    fn add_updates(&self, updates: &mut Vec<Revision>) {
+        for (_, cell) in self.cells.entries() {
+            updates.extend(cell.history_revisions());
+        }
+    }
+
+    pub(crate) fn dirty_external(
+        &self,
+        id: TypedInputIdWithKind<T, ExternalId<T>>,
+        revision: Revision,
+    ) -> Result<(), Error> {
+        self.externals.lookup(id)?.dirty(revision);
+        Ok(())
+    }
+
+    pub(crate) fn modify_cell<U>(
+        &mut self,
+        id: TypedInputIdWithKind<T, CellId<T>>,
+        revision: Revision,
+        f: impl FnOnce(&mut T) -> U,
+    ) -> Result<U, Error> {
+        let cell = self.cells.lookup_mut(id)?;
+        Ok(cell.update_with(f, revision))
+    }
+
+    pub(crate) fn update_list<U>(
+        &mut self,
+        id: TypedInputIdWithKind<T, ListId<T>>,
+        revision: Revision,
+        f: impl FnOnce(&mut T) -> Result<U, Error>,
+    ) -> Result<U, Error> {
+        let list = self.lists.lookup_mut(id)?;
+        list.try_update_with(f, revision)
+    }
+
+    fn contains(&self, id: TypedInputId<T>) -> bool {
+        match id.kind() {
+            IdKind::CellId => self.cells.contains(id.downcast(CellId)),
+            IdKind::ConstantId => self.constants.contains(id.downcast(ConstantId)),
+            IdKind::DerivedId => self.derived.contains(id.downcast(DerivedId)),
+            IdKind::ListId => self.lists.contains(id.downcast(ListId)),
+            IdKind::ExternalId => self.externals.contains(id.downcast(ExternalId)),
+        }
+    }
+
+    /// Remove an input, returning a function that puts it back, or `None` if it was already
+    /// removed.
+    fn take(&mut self, id: TypedInputId<T>) -> Option<Restore<T, M>> {
+        match id.kind() {
+            IdKind::CellId => {
+                let id = id.downcast(CellId);
+                let cell = self.cells.remove(id)?;
+                Some(Box::new(move |inputs| inputs.cells.restore(id, cell)))
+            }
+            IdKind::ConstantId => {
+                let id = id.downcast(ConstantId);
+                let constant = self.constants.remove(id)?;
+                Some(Box::new(move |inputs| {
+                    inputs.constants.restore(id, constant)
+                }))
+            }
+            IdKind::DerivedId => {
+                let id = id.downcast(DerivedId);
+                let derived = self.derived.remove(id)?;
+                Some(Box::new(move |inputs| inputs.derived.restore(id, derived)))
+            }
+            IdKind::ListId => {
+                let id = id.downcast(ListId);
+                let list = self.lists.remove(id)?;
+                Some(Box::new(move |inputs| inputs.lists.restore(id, list)))
+            }
+            IdKind::ExternalId => {
+                let id = id.downcast(ExternalId);
+                let external = self.externals.remove(id)?;
+                Some(Box::new(move |inputs| {
+                    inputs.externals.restore(id, external)
+                }))
+            }
+        }
+    }
+
+    fn snapshot_cell(&self, id: TypedInputIdWithKind<T, CellId<T>>) -> Result<(T, Revision), Error>
+    where
+        T: Clone,
+    {
+        let cell = self.cells.lookup(id)?;
+        Ok((cell.read(), cell.revision()))
+    }
+
+    fn snapshot_list(&self, id: TypedInputIdWithKind<T, ListId<T>>) -> Result<(T, Revision), Error>
+    where
+        T: Clone,
+    {
+        let list = self.lists.lookup(id)?;
+        Ok((list.read(), list.revision()))
+    }
+}
+
+/// The inputs of one type, with the type and the mode of the timeline erased, so that an
+/// `EvaluationContext` can read them without knowing either.
+pub trait DynTypedInputs {
+    fn as_any(&self) -> &dyn Any;
+
+    fn as_any_mut(&mut self) -> &mut dyn Any;
+
+    /// Lend the value of `id` to `f`, as a `&T`.
+    fn dyn_with_value(
+        &self,
+        id: DynId,
+        ctx: &mut EvaluationContext,
+        f: &mut dyn FnMut(&dyn Any),
+    ) -> Result<(), Error>;
+
+    fn dyn_revision(&self, id: DynId) -> Result<Revision, Error>;
+
+    /// Bring the derived computation `id` up to date, without tracking it as a dependency.
+    fn dyn_refresh(&self, id: DynId, ctx: &mut EvaluationContext) -> Result<(), Error>;
+
+    fn dyn_add_to_graph(&self, graph: &mut DependencyGraph);
+
+    fn dyn_add_updates(&self, updates: &mut Vec<Revision>);
+
+    fn dyn_explain(&self, id: DynId, since: Revision) -> Result<Explanation, Error>;
+}
+
+impl<T: 'static, M: Mode> DynTypedInputs for TypedInputs<T, M> {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+
+    fn dyn_with_value(
+        &self,
+        id: DynId,
+        ctx: &mut EvaluationContext,
+        f: &mut dyn FnMut(&dyn Any),
+    ) -> Result<(), Error> {
+        self.with_value(id.try_downcast()?, ctx, |value| f(value))
+    }
+
+    fn dyn_revision(&self, id: DynId) -> Result<Revision, Error> {
+        self.revision(id.try_downcast()?)
+    }
+
+    fn dyn_refresh(&self, id: DynId, ctx: &mut EvaluationContext) -> Result<(), Error> {
+        let id = id.try_downcast::<T>()?.try_downcast(DerivedId)?;
+        self.refresh_derived(id, ctx)?;
+        Ok(())
+    }
+
+    fn dyn_add_to_graph(&self, graph: &mut DependencyGraph) {
+        self.add_to_graph(graph)
+    }
+
+    fn dyn_add_updates(&self, updates: &mut Vec<Revision>) {
+        self.add_updates(updates)
+    }
+
+    fn dyn_explain(&self, id: DynId, since: Revision) -> Result<Explanation, Error> {
+        let tag = self.tag(id.try_downcast()?)?;
+        Ok(Explanation::new(id, tag, since))
+    }
+}
+
+fn refresh_memo(ctx: &mut EvaluationContext, id: DynId) -> Result<(), Error> {
+    let inputs = ctx.inputs;
+    inputs.dyn_refresh(id, ctx)
+}
+
+/// The inputs of a timeline, with the mode of the timeline erased. This is how an
+/// `EvaluationContext` reads them.
+pub(crate) trait DynInputs: Debug {
+    fn dyn_with_value(
+        &self,
+        id: DynId,
+        ctx: &mut EvaluationContext,
+        f: &mut dyn FnMut(&dyn Any),
+    ) -> Result<(), Error>;
+
+    fn dyn_revision(&self, id: DynId) -> Result<Revision, Error>;
+
+    fn dyn_refresh(&self, id: DynId, ctx: &mut EvaluationContext) -> Result<(), Error>;
+
+    fn dyn_explain(&self, id: DynId, since: Revision) -> Result<Explanation, Error>;
+}
+
+impl dyn DynInputs + '_ {
+    pub(crate) fn value<T>(
+        &self,
+        id: impl Into<TypedInputId<T>>,
+        ctx: &mut EvaluationContext,
+    ) -> Result<T, Error>
+    where
+        T: Clone + 'static,
+    {
+        self.with_value(id, ctx, T::clone)
+    }
+
+    pub(crate) fn with_value<T, U>(
+        &self,
+        id: impl Into<TypedInputId<T>>,
+        ctx: &mut EvaluationContext,
+        f: impl FnOnce(&T) -> U,
+    ) -> Result<U, Error>
+    where
+        T: 'static,
+    {
+        let mut f = Some(f);
+        let mut result = None;
+
+        self.dyn_with_value(DynId::from(id.into()), ctx, &mut |value| {
+            let value = value
+                .downcast_ref()
+                .expect("the inputs of a type lend values of that type");
+            result = f.take().map(|f| f(value));
+        })?;
+
+        Ok(result.expect("the inputs didn't lend a value"))
+    }
+
+    pub(crate) fn revision<T>(&self, id: impl Into<TypedInputId<T>>) -> Result<Revision, Error>
+    where
+        T: 'static,
+    {
+        self.dyn_revision(DynId::from(id.into()))
+    }
+}
+
+impl<M: Mode> DynInputs for Inputs<M> {
+    fn dyn_with_value(
+        &self,
+        id: DynId,
+        ctx: &mut EvaluationContext,
+        f: &mut dyn FnMut(&dyn Any),
+    ) -> Result<(), Error> {
+        self.dyn_map_for(id)?.dyn_with_value(id, ctx, f)
+    }
+
+    fn dyn_revision(&self, id: DynId) -> Result<Revision, Error> {
+        self.dyn_map_for(id)?.dyn_revision(id)
+    }
+
+    fn dyn_refresh(&self, id: DynId, ctx: &mut EvaluationContext) -> Result<(), Error> {
+        self.dyn_map_for(id)?.dyn_refresh(id, ctx)
+    }
+
+    fn dyn_explain(&self, id: DynId, since: Revision) -> Result<Explanation, Error> {
+        self.dyn_map_for(id)?.dyn_explain(id, since)
     }
 }
 
-#[derive(Default)]
-pub(crate) struct Inputs {
-    map: TypeMap,
+/// Undoes a single change to the inputs.
+pub(crate) type Undo<M> = Box<dyn FnOnce(&mut Inputs<M>)>;
+
+/// The inputs of a timeline in mode `M`. The inputs of each type are boxed the way that `M`
+/// expects, so the inputs of a `Timeline<Shared>` are `Send + Sync`.
+pub(crate) struct Inputs<M: Mode> {
+    // in the order that the types were registered, so that the dependency graph is stable
+    map: IndexMap<TypeId, Box<M::Inputs>>,
     types: Vec<String>,
+    // the cells that were given a name, so that they can be saved in a snapshot
+    #[cfg(feature = "serde")]
+    persisted: IndexMap<String, Persisted<M>>,
+}
+
+impl<M: Mode> Default for Inputs<M> {
+    fn default() -> Inputs<M> {
+        Inputs {
+            map: IndexMap::new(),
+            types: vec![],
+            #[cfg(feature = "serde")]
+            persisted: IndexMap::new(),
+        }
+    }
 }
 
-impl Inputs {
-    pub(crate) fn value<T>(&self, id: impl Into<TypedInputId<T>>, ctx: &mut EvaluationContext) -> T
+impl<M: Mode> Inputs<M> {
+    pub(crate) fn add_to_graph(&self, graph: &mut DependencyGraph) {
+        for inputs in self.map.values() {
+            inputs.dyn_add_to_graph(graph);
+        }
+    }
+
+    #[cfg(feature = "serde")]
+    pub(crate) fn persist(&mut self, name: String, persisted: Persisted<M>) -> Result<(), Error> {
+        if self.persisted.contains_key(&name) {
+            return Err(Error::NameTaken { name });
+        }
+
+        self.persisted.insert(name, persisted);
+        Ok(())
+    }
+
+    #[cfg(feature = "serde")]
+    pub(crate) fn persisted(&self) -> impl Iterator<Item = (&String, &Persisted<M>)> + '_ {
+        self.persisted.iter()
+    }
+
+    /// Every revision that a cell with a history remembers, oldest first, without duplicates.
+    pub(crate) fn updates(&self) -> Vec<Revision> {
+        let mut updates = vec![];
+
+        for inputs in self.map.values() {
+            inputs.dyn_add_updates(&mut updates);
+        }
+
+        updates.sort();
+        updates.dedup();
+        updates
+    }
+
+    pub(crate) fn revision<T>(&self, id: impl Into<TypedInputId<T>>) -> Result<Revision, Error>
     where
-        T: Debug + Clone + 'static,
+        T: 'static,
     {
         let id = id.into();
+        self.map_for::<T>()?.revision(id)
+    }
 
-        match id.kind() {
-            IdKind::CellId => self.map_for::<T>().read_cell(id.downcast(CellId), ctx),
-            IdKind::DerivedId => self
-                .map_for::<T>()
-                .compute_derived(id.downcast(DerivedId), ctx),
-            IdKind::ListId => unimplemented!("Inputs::get_value for lists"),
+    pub(crate) fn tag<T>(&self, id: impl Into<TypedInputId<T>>) -> Result<ReactiveTag, Error>
+    where
+        T: 'static,
+    {
+        let id = id.into();
+        self.map_for::<T>()?.tag(id)
+    }
+
+    pub(crate) fn contains<T>(&self, id: impl Into<TypedInputId<T>>) -> bool
+    where
+        T: 'static,
+    {
+        let id = id.into();
+
+        match self.map_for::<T>() {
+            Ok(map) => map.contains(id),
+            Err(_) => false,
         }
     }
 
-    pub(crate) fn revision<T>(&self, id: impl Into<TypedInputId<T>>) -> Option<Revision>
+    pub(crate) fn delete<T>(&mut self, id: impl Into<TypedInputId<T>>) -> bool
+    where
+        T: 'static,
+    {
+        self.take(id).is_some()
+    }
+
+    /// Like `delete`, but returns a way to undo the deletion.
+    pub(crate) fn take<T>(&mut self, id: impl Into<TypedInputId<T>>) -> Option<Undo<M>>
     where
-        T: Debug + Clone + 'static,
+        T: 'static,
     {
         let id = id.into();
-        self.map_for::<T>().revision(id)
+        let restore = self.existing_map_for_mut::<T>().ok()?.take(id)?;
+        Some(Box::new(move |inputs: &mut Inputs<M>| {
+            restore(
+                inputs
+                    .existing_map_for_mut::<T>()
+                    .expect("a map that an input was taken from can't disappear"),
+            )
+        }))
+    }
+
+    /// The current value of a cell, without tracking the read.
+    pub(crate) fn borrow_cell<T>(&self, id: TypedInputIdWithKind<T, CellId<T>>) -> Result<&T, Error>
+    where
+        T: 'static,
+    {
+        Ok(self.map_for::<T>()?.cells.lookup(id)?.borrow())
+    }
+
+    /// Record the current value and revision of a cell, so that a later update can be undone.
+    pub(crate) fn snapshot_cell<T>(
+        &self,
+        id: TypedInputIdWithKind<T, CellId<T>>,
+    ) -> Result<Undo<M>, Error>
+    where
+        T: Clone + 'static,
+    {
+        let (value, revision) = self.map_for::<T>()?.snapshot_cell(id)?;
+        Ok(Inputs::restore_cell(id, value, revision))
+    }
+
+    /// Undo an update to a cell, by putting back the value and revision that it replaced.
+    pub(crate) fn restore_cell<T>(
+        id: TypedInputIdWithKind<T, CellId<T>>,
+        value: T,
+        revision: Revision,
+    ) -> Undo<M>
+    where
+        T: 'static,
+    {
+        Box::new(move |inputs: &mut Inputs<M>| {
+            inputs
+                .existing_map_for_mut::<T>()
+                .and_then(|map| map.restore_cell(id, value, revision))
+                .expect("an updated cell can't disappear before it's restored");
+        })
+    }
+
+    /// Record the current items and revision of a list, so that later edits can be undone.
+    pub(crate) fn snapshot_list<T>(
+        &self,
+        id: TypedInputIdWithKind<ReactiveList<T>, ListId<ReactiveList<T>>>,
+    ) -> Result<Undo<M>, Error>
+    where
+        T: Clone + 'static,
+    {
+        let (list, revision) = self.map_for::<ReactiveList<T>>()?.snapshot_list(id)?;
+        Ok(Box::new(move |inputs: &mut Inputs<M>| {
+            inputs
+                .update_list(id, revision, |current| {
+                    *current = list;
+                    Ok(())
+                })
+                .expect("a list that was snapshotted can't disappear before it's restored")
+        }))
     }
 
     // fn split(&mut self) -> PartitionedInputs {
@@ -197,12 +911,12 @@ impl Inputs {
 
     pub(crate) fn add_derived<T>(
         &mut self,
-        derived: ReactiveDerived<T>,
+        derived: ReactiveDerived<T, M>,
     ) -> TypedInputIdWithKind<T, DerivedId<T>>
     where
-        T: Debug + Clone + 'static,
+        T: Storable<M> + 'static,
     {
-        self.map_for_mut::<T>().add_derived(derived)
+        self.map_for_mut::<T>(T::new_inputs).add_derived(derived)
     }
 
     pub(crate) fn add_cell<T>(
@@ -210,50 +924,103 @@ impl Inputs {
         cell: ReactiveCell<T>,
     ) -> TypedInputIdWithKind<T, CellId<T>>
     where
-        T: Debug + Clone + 'static,
+        T: Storable<M> + 'static,
     {
-        self.map_for_mut::<T>().add_cell(cell)
+        self.map_for_mut::<T>(T::new_inputs).add_cell(cell)
     }
 
-    fn register_map<T: Debug + Clone + 'static>(&mut self) {
-        let type_name = std::any::type_name::<T>();
+    pub(crate) fn add_constant<T>(
+        &mut self,
+        constant: ReactiveCell<T>,
+    ) -> TypedInputIdWithKind<T, ConstantId<T>>
+    where
+        T: Storable<M> + 'static,
+    {
+        self.map_for_mut::<T>(T::new_inputs).add_constant(constant)
+    }
 
-        if self.map.contains::<TypedInputs<T>>() {
-            panic!(
-                "Attempted to register a map for {:?} but it was already registered",
-                std::any::type_name::<T>()
-            );
-        } else {
-            self.map.insert::<TypedInputs<T>>(TypedInputs::for_type());
-            self.types.push(type_name.to_string());
-        }
+    pub(crate) fn add_list<T>(
+        &mut self,
+        list: ReactiveCell<ReactiveList<T>>,
+    ) -> TypedInputIdWithKind<ReactiveList<T>, ListId<ReactiveList<T>>>
+    where
+        T: Storable<M> + 'static,
+    {
+        self.map_for_mut::<ReactiveList<T>>(T::new_list_inputs)
+            .add_list(list)
     }
 
-    pub(crate) fn read_map_for<T: Debug + Clone + 'static>(&self) -> &TypedInputs<T> {
-        if self.map.contains::<TypedInputs<T>>() {
-            self.map.get::<TypedInputs<T>>().unwrap()
-        } else {
-            panic!(
+    pub(crate) fn add_external<T>(
+        &mut self,
+        external: ReactiveExternal<T, M>,
+    ) -> TypedInputIdWithKind<T, ExternalId<T>>
+    where
+        T: Storable<M> + 'static,
+    {
+        self.map_for_mut::<T>(T::new_inputs).add_external(external)
+    }
+
+    pub(crate) fn dirty_external<T>(
+        &self,
+        id: TypedInputIdWithKind<T, ExternalId<T>>,
+        revision: Revision,
+    ) -> Result<(), Error>
+    where
+        T: 'static,
+    {
+        self.map_for::<T>()?.dirty_external(id, revision)
+    }
+
+    pub(crate) fn read_map_for<T: 'static>(&self) -> &TypedInputs<T, M> {
+        match self.map_for::<T>() {
+            Ok(map) => map,
+            Err(_) => panic!(
                 "Attempted to get map for {:?} but it wasn't registered",
                 std::any::type_name::<T>()
-            )
+            ),
         }
     }
 
-    fn map_for_mut<T: Debug + Clone + 'static>(&mut self) -> &mut TypedInputs<T> {
-        if self.map.contains::<TypedInputs<T>>() {
-            self.map.get_mut::<TypedInputs<T>>().unwrap()
-        } else {
-            self.register_map::<T>();
-            self.map.get_mut::<TypedInputs<T>>().unwrap()
-        }
+    /// The inputs for `T`, registering them with `new` if there weren't any yet.
+    fn map_for_mut<T: 'static>(&mut self, new: fn() -> Box<M::Inputs>) -> &mut TypedInputs<T, M> {
+        let types = &mut self.types;
+
+        self.map
+            .entry(TypeId::of::<T>())
+            .or_insert_with(|| {
+                types.push(type_name::<T>().to_string());
+                new()
+            })
+            .as_any_mut()
+            .downcast_mut()
+            .expect("the inputs registered for a type are always inputs of that type")
     }
 
-    fn map_for<T: Debug + Clone + 'static>(&self) -> &TypedInputs<T> {
-        if self.map.contains::<TypedInputs<T>>() {
-            self.map.get::<TypedInputs<T>>().unwrap()
-        } else {
-            panic!("Could not get map for {}", type_name::<T>());
+    fn map_for<T: 'static>(&self) -> Result<&TypedInputs<T, M>, Error> {
+        self.map
+            .get(&TypeId::of::<T>())
+            .and_then(|inputs| inputs.as_any().downcast_ref())
+            .ok_or_else(|| Error::UnknownType {
+                type_name: type_name::<T>(),
+            })
+    }
+
+    // unlike `map_for_mut`, this doesn't register a map for `T` if there wasn't one yet
+    fn existing_map_for_mut<T: 'static>(&mut self) -> Result<&mut TypedInputs<T, M>, Error> {
+        self.map
+            .get_mut(&TypeId::of::<T>())
+            .and_then(|inputs| inputs.as_any_mut().downcast_mut())
+            .ok_or_else(|| Error::UnknownType {
+                type_name: type_name::<T>(),
+            })
+    }
+
+    fn dyn_map_for(&self, id: DynId) -> Result<&M::Inputs, Error> {
+        match self.map.get(&id.type_id()) {
+            Some(inputs) => Ok(inputs),
+            None => Err(Error::UnknownType {
+                type_name: id.type_name(),
+            }),
         }
     }
 
@@ -262,14 +1029,42 @@ impl Inputs {
         id: TypedInputIdWithKind<T, CellId<T>>,
         value: T,
         revision: Revision,
-    ) where
-        T: Debug + Clone + 'static,
+    ) -> Result<(T, Revision), Error>
+    where
+        T: 'static,
+    {
+        self.existing_map_for_mut::<T>()?
+            .update_cell(id, value, revision)
+    }
+
+    pub(crate) fn modify_cell<T, U>(
+        &mut self,
+        id: TypedInputIdWithKind<T, CellId<T>>,
+        revision: Revision,
+        f: impl FnOnce(&mut T) -> U,
+    ) -> Result<U, Error>
+    where
+        T: 'static,
+    {
+        self.existing_map_for_mut::<T>()?
+            .modify_cell(id, revision, f)
+    }
+
+    pub(crate) fn update_list<T, U>(
+        &mut self,
+        id: TypedInputIdWithKind<ReactiveList<T>, ListId<ReactiveList<T>>>,
+        revision: Revision,
+        f: impl FnOnce(&mut ReactiveList<T>) -> Result<U, Error>,
+    ) -> Result<U, Error>
+    where
+        T: 'static,
     {
-        self.map_for_mut::<T>().update_cell(id, value, revision);
+        self.existing_map_for_mut::<ReactiveList<T>>()?
+            .update_list(id, revision, f)
     }
 }
 
-impl Debug for Inputs {
+impl<M: Mode> Debug for Inputs<M> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         let mut debug = IndexMap::new();
         debug.insert("types", &self.types);
@@ -1,13 +1,33 @@
 pub(crate) mod dyn_id;
 pub(crate) mod evaluation_context;
+pub(crate) mod explain;
+pub(crate) mod graph;
 pub(crate) mod id;
 pub(crate) mod inputs;
+pub(crate) mod mode;
 pub(crate) mod partition;
 pub(crate) mod revision;
+pub(crate) mod shared;
+#[cfg(feature = "serde")]
+pub(crate) mod snapshot;
+#[allow(clippy::module_inception)]
 pub(crate) mod timeline;
 
 pub use dyn_id::DynId;
 pub use evaluation_context::EvaluationContext;
-pub use id::{CellId, DerivedId, IdKindFor, TypedInputId, TypedInputIdWithKind};
+pub use explain::{Cause, Check, Explanation};
+pub use graph::{DependencyGraph, GraphEdge, GraphNode};
+pub use id::{
+    CellId, ConstantId, DerivedId, ExternalId, IdKind, IdKindFor, ListId, TypedInputId,
+    TypedInputIdWithKind,
+};
+pub use mode::{Local, Mode, Shared, Storable};
 pub use revision::Revision;
-pub use timeline::{RenderTransaction, Timeline};
+pub use shared::SharedTimeline;
+#[cfg(feature = "serde")]
+pub use snapshot::Snapshot;
+#[cfg(feature = "rayon")]
+pub use timeline::ParRenderTransaction;
+pub use timeline::{
+    RenderTransaction, SetupTransaction, StagedTransaction, Timeline, UpdateTransaction,
+};
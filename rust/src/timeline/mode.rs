@@ -0,0 +1,199 @@
+use std::fmt::Debug;
+
+use crate::{
+    inputs::{DynamicComputation, ExternalStorage, ReactiveList},
+    outputs::Output,
+};
+
+use super::inputs::{DynTypedInputs, TypedInputs};
+
+mod sealed {
+    pub trait Sealed {}
+
+    impl Sealed for super::Local {}
+    impl Sealed for super::Shared {}
+}
+
+/// Whether a timeline can be shared between threads. A `Timeline` is `Local` by default, and can
+/// hold values, computations, storage and outputs that aren't `Send` (such as an `Rc` or a DOM
+/// handle). A `Timeline<Shared>`, which is what a `SharedTimeline` wraps, only holds ones that are
+/// `Send + Sync`.
+///
+/// The mode decides how a timeline boxes everything that it holds, so a `Timeline<Shared>` is
+/// `Send + Sync` because every box in it is.
+pub trait Mode: sealed::Sealed + Debug + Default + Send + Sync + 'static {
+    /// How a timeline in this mode boxes the inputs of each type.
+    #[doc(hidden)]
+    type Inputs: ?Sized + DynTypedInputs;
+
+    /// How a timeline in this mode boxes its derived computations.
+    #[doc(hidden)]
+    type Computation<T: 'static>: ?Sized + DynamicComputation<T>;
+
+    /// How a timeline in this mode boxes its external storage.
+    #[doc(hidden)]
+    type Storage<T: 'static>: ?Sized + ExternalStorage<T>;
+
+    /// How the program and the tree outputs of a timeline in this mode box their outputs.
+    type Output: ?Sized + Output;
+
+    fn as_output(output: &Self::Output) -> &dyn Output;
+
+    fn as_output_mut(output: &mut Self::Output) -> &mut dyn Output;
+
+    fn into_output(output: Box<Self::Output>) -> Box<dyn Output>;
+}
+
+/// The mode of a timeline that stays on one thread.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct Local;
+
+/// The mode of a timeline that can be shared between threads.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct Shared;
+
+impl Mode for Local {
+    type Inputs = dyn DynTypedInputs;
+    type Computation<T: 'static> = dyn DynamicComputation<T>;
+    type Storage<T: 'static> = dyn ExternalStorage<T>;
+    type Output = dyn Output;
+
+    fn as_output(output: &dyn Output) -> &dyn Output {
+        output
+    }
+
+    fn as_output_mut(output: &mut dyn Output) -> &mut dyn Output {
+        output
+    }
+
+    fn into_output(output: Box<dyn Output>) -> Box<dyn Output> {
+        output
+    }
+}
+
+impl Mode for Shared {
+    type Inputs = dyn DynTypedInputs + Send + Sync;
+    type Computation<T: 'static> = dyn DynamicComputation<T> + Send + Sync;
+    type Storage<T: 'static> = dyn ExternalStorage<T> + Send + Sync;
+    type Output = dyn Output + Send + Sync;
+
+    fn as_output(output: &(dyn Output + Send + Sync)) -> &dyn Output {
+        output
+    }
+
+    fn as_output_mut(output: &mut (dyn Output + Send + Sync)) -> &mut dyn Output {
+        output
+    }
+
+    fn into_output(output: Box<dyn Output + Send + Sync>) -> Box<dyn Output> {
+        output
+    }
+}
+
+/// Implemented by everything that a timeline in mode `M` can hold: anything at all for a `Local`
+/// timeline, and anything that's `Send + Sync` for a `Shared` one.
+///
+/// The methods box a value the way that mode `M` expects, which is only possible where the
+/// compiler knows that the value is `Send + Sync`.
+pub trait Storable<M: Mode> {
+    #[doc(hidden)]
+    fn into_boxed_output(self) -> Box<M::Output>
+    where
+        Self: Output + Sized;
+
+    #[doc(hidden)]
+    fn into_boxed_computation<T: 'static>(self) -> Box<M::Computation<T>>
+    where
+        Self: DynamicComputation<T> + Sized + 'static;
+
+    #[doc(hidden)]
+    fn into_boxed_storage<T: 'static>(self) -> Box<M::Storage<T>>
+    where
+        Self: ExternalStorage<T> + Sized + 'static;
+
+    /// Empty inputs for values of this type.
+    #[doc(hidden)]
+    fn new_inputs() -> Box<M::Inputs>
+    where
+        Self: Sized + 'static;
+
+    /// Empty inputs for lists of this type.
+    #[doc(hidden)]
+    fn new_list_inputs() -> Box<M::Inputs>
+    where
+        Self: Sized + 'static;
+}
+
+impl<T: ?Sized> Storable<Local> for T {
+    fn into_boxed_output(self) -> Box<dyn Output>
+    where
+        Self: Output + Sized,
+    {
+        Box::new(self)
+    }
+
+    fn into_boxed_computation<U: 'static>(self) -> Box<dyn DynamicComputation<U>>
+    where
+        Self: DynamicComputation<U> + Sized + 'static,
+    {
+        Box::new(self)
+    }
+
+    fn into_boxed_storage<U: 'static>(self) -> Box<dyn ExternalStorage<U>>
+    where
+        Self: ExternalStorage<U> + Sized + 'static,
+    {
+        Box::new(self)
+    }
+
+    fn new_inputs() -> Box<dyn DynTypedInputs>
+    where
+        Self: Sized + 'static,
+    {
+        Box::new(TypedInputs::<T, Local>::for_type())
+    }
+
+    fn new_list_inputs() -> Box<dyn DynTypedInputs>
+    where
+        Self: Sized + 'static,
+    {
+        Box::new(TypedInputs::<ReactiveList<T>, Local>::for_type())
+    }
+}
+
+impl<T: ?Sized + Send + Sync> Storable<Shared> for T {
+    fn into_boxed_output(self) -> Box<dyn Output + Send + Sync>
+    where
+        Self: Output + Sized,
+    {
+        Box::new(self)
+    }
+
+    fn into_boxed_computation<U: 'static>(self) -> Box<dyn DynamicComputation<U> + Send + Sync>
+    where
+        Self: DynamicComputation<U> + Sized + 'static,
+    {
+        Box::new(self)
+    }
+
+    fn into_boxed_storage<U: 'static>(self) -> Box<dyn ExternalStorage<U> + Send + Sync>
+    where
+        Self: ExternalStorage<U> + Sized + 'static,
+    {
+        Box::new(self)
+    }
+
+    fn new_inputs() -> Box<dyn DynTypedInputs + Send + Sync>
+    where
+        Self: Sized + 'static,
+    {
+        Box::new(TypedInputs::<T, Shared>::for_type())
+    }
+
+    fn new_list_inputs() -> Box<dyn DynTypedInputs + Send + Sync>
+    where
+        Self: Sized + 'static,
+    {
+        Box::new(TypedInputs::<ReactiveList<T>, Shared>::for_type())
+    }
+}
@@ -1,6 +1,9 @@
 use atomig::{Atom, Atomic};
-use std::cmp::Ordering;
 use std::sync::atomic;
+use std::{
+    cmp::Ordering,
+    fmt::{self, Display},
+};
 
 #[derive(Debug, Copy, Clone, Ord, PartialOrd, Eq, PartialEq, Atom)]
 pub struct Revision {
@@ -26,6 +29,10 @@ impl Revision {
         Revision { timestamp: 0 }
     }
 
+    pub(crate) fn is_constant(self) -> bool {
+        self.timestamp == 0
+    }
+
     pub(crate) fn increment(self) -> Revision {
         Revision {
             timestamp: self.timestamp + 1,
@@ -39,6 +46,16 @@ impl Revision {
     }
 }
 
+impl Display for Revision {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.is_constant() {
+            write!(f, "«const»")
+        } else {
+            write!(f, "«{}»", self.timestamp)
+        }
+    }
+}
+
 #[derive(Debug)]
 pub(crate) struct AtomicRevision {
     revision: Atomic<Revision>,
@@ -0,0 +1,64 @@
+use std::sync::Arc;
+
+use parking_lot::RwLock;
+
+use super::{RenderTransaction, SetupTransaction, Shared, Timeline, UpdateTransaction};
+
+/// A timeline that can be shared between threads. Cloning a `SharedTimeline` produces another
+/// handle to the same timeline.
+///
+/// Any number of threads can render at the same time, while setup and update transactions run one
+/// at a time, and never overlap with a render. This means that:
+///
+/// - a render observes the revision of the last update that finished before the render started,
+///   and every input that it reads stays at that revision until the render is done
+/// - a render never observes part of an update
+/// - an update that starts while renders are in progress waits for them to finish
+///
+/// Since computations and external storage may run on any thread that renders, a shared timeline
+/// is a `Timeline<Shared>`, which only holds values, computations, storage and outputs that are
+/// `Send + Sync`.
+#[derive(Debug, Clone, Default)]
+pub struct SharedTimeline {
+    timeline: Arc<RwLock<Timeline<Shared>>>,
+}
+
+impl From<Timeline<Shared>> for SharedTimeline {
+    fn from(timeline: Timeline<Shared>) -> SharedTimeline {
+        SharedTimeline {
+            timeline: Arc::new(RwLock::new(timeline)),
+        }
+    }
+}
+
+impl SharedTimeline {
+    pub fn new() -> SharedTimeline {
+        SharedTimeline::from(Timeline::shared())
+    }
+
+    pub fn setup<U>(&self, f: impl FnOnce(&mut SetupTransaction<'_, Shared>) -> U) -> U {
+        f(&mut self.timeline.write().setup())
+    }
+
+    /// Run an update transaction, after every render that's already in progress finished.
+    pub fn update<U>(&self, f: impl FnOnce(&mut UpdateTransaction<'_, Shared>) -> U) -> U {
+        f(&mut self.timeline.write().update())
+    }
+
+    /// Run a render transaction, which can happen at the same time as renders on other threads.
+    pub fn render<U>(&self, f: impl FnOnce(&mut RenderTransaction) -> U) -> U {
+        f(&mut self.timeline.read().begin())
+    }
+
+    /// Borrow the timeline, for example to check the revision of an input. Other threads can
+    /// render while the timeline is borrowed, but they can't update it.
+    pub fn read<U>(&self, f: impl FnOnce(&Timeline<Shared>) -> U) -> U {
+        f(&self.timeline.read())
+    }
+
+    /// Borrow the timeline mutably, for example to stage a transaction or to render the timeline's
+    /// program. Nothing else happens on the timeline until `f` returns.
+    pub fn write<U>(&self, f: impl FnOnce(&mut Timeline<Shared>) -> U) -> U {
+        f(&mut self.timeline.write())
+    }
+}
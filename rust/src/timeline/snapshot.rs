@@ -0,0 +1,121 @@
+use std::collections::BTreeMap;
+
+use getset::Getters;
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::Error;
+
+use super::{inputs::Inputs, CellId, DynId, Mode, TypedInputIdWithKind, UpdateTransaction};
+
+/// The values of the named cells of a timeline, keyed by name. Ids are handed out in the order
+/// that inputs are set up, so they can't identify a cell across sessions, but the names can.
+///
+/// A snapshot serializes as a map from names to values, so it can be saved in any format that
+/// serde supports.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, Getters)]
+#[serde(transparent)]
+pub struct Snapshot {
+    #[get = "pub"]
+    cells: BTreeMap<String, Value>,
+}
+
+/// Writes a restored value into its cell.
+pub(crate) type Restore<M> = Box<dyn FnOnce(&mut UpdateTransaction<M>)>;
+
+/// Serializes the value of a cell, or returns `None` if the cell was deleted.
+type Save<M> = fn(&Inputs<M>, id: DynId, name: &str) -> Result<Option<Value>, Error>;
+
+/// Deserializes a value for a cell.
+type Load<M> = fn(Value, id: DynId, name: &str) -> Result<Restore<M>, Error>;
+
+/// How to save and restore a cell that was given a name. This only holds the cell's id and a pair
+/// of functions, so the inputs of a `Timeline<Shared>` stay `Send + Sync`.
+pub(crate) struct Persisted<M: Mode> {
+    id: DynId,
+    save: Save<M>,
+    load: Load<M>,
+}
+
+impl<M: Mode> Persisted<M> {
+    pub(crate) fn new<T>(id: TypedInputIdWithKind<T, CellId<T>>) -> Persisted<M>
+    where
+        T: Serialize + DeserializeOwned + 'static,
+    {
+        Persisted {
+            id: id.to_dyn(),
+            save: save::<T, M>,
+            load: load::<T, M>,
+        }
+    }
+}
+
+fn save<T, M>(inputs: &Inputs<M>, id: DynId, name: &str) -> Result<Option<Value>, Error>
+where
+    T: Serialize + 'static,
+    M: Mode,
+{
+    let id = id.try_downcast::<T>()?.try_downcast(CellId)?;
+
+    let value = match inputs.borrow_cell(id) {
+        Ok(value) => value,
+        // a deleted cell doesn't have a value to save
+        Err(Error::Removed { .. }) => return Ok(None),
+        Err(error) => return Err(error),
+    };
+
+    serde_json::to_value(value)
+        .map(Some)
+        .map_err(|error| Error::Snapshot {
+            name: name.to_string(),
+            message: error.to_string(),
+        })
+}
+
+fn load<T, M>(value: Value, id: DynId, name: &str) -> Result<Restore<M>, Error>
+where
+    T: DeserializeOwned + 'static,
+    M: Mode,
+{
+    let id = id.try_downcast::<T>()?.try_downcast(CellId)?;
+    let value: T = serde_json::from_value(value).map_err(|error| Error::Snapshot {
+        name: name.to_string(),
+        message: error.to_string(),
+    })?;
+
+    Ok(Box::new(move |transaction: &mut UpdateTransaction<M>| {
+        // like a deleted cell isn't saved, it isn't restored either
+        let _ = transaction.try_update(id, value);
+    }))
+}
+
+impl Snapshot {
+    pub(crate) fn take<'a, M: Mode>(
+        persisted: impl Iterator<Item = (&'a String, &'a Persisted<M>)>,
+        inputs: &Inputs<M>,
+    ) -> Result<Snapshot, Error> {
+        let mut cells = BTreeMap::new();
+
+        for (name, persisted) in persisted {
+            if let Some(value) = (persisted.save)(inputs, persisted.id, name)? {
+                cells.insert(name.clone(), value);
+            }
+        }
+
+        Ok(Snapshot { cells })
+    }
+
+    /// Deserialize the value of every named cell that's in the snapshot. Nothing is written until
+    /// every value was deserialized, so a snapshot that doesn't fit the timeline leaves it alone.
+    pub(crate) fn restores<'a, M: Mode>(
+        &self,
+        persisted: impl Iterator<Item = (&'a String, &'a Persisted<M>)>,
+    ) -> Result<Vec<Restore<M>>, Error> {
+        persisted
+            .filter_map(|(name, persisted)| {
+                let value = self.cells.get(name)?;
+                Some((persisted.load)(value.clone(), persisted.id, name))
+            })
+            .collect()
+    }
+}
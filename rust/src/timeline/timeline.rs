@@ -1,108 +1,709 @@
-use std::fmt::Debug;
-
-use derive_new::new;
+use std::{
+    fmt::Debug,
+    hash::Hash,
+    ops::{Deref, DerefMut},
+};
 
 use crate::{
-    inputs::{DerivedTag, DynamicComputation, ReactiveCell, ReactiveDerived, Tag},
-    outputs::PrimitiveOutput,
+    inputs::{
+        CopyIntoReactiveList, DerivedTag, DynamicComputation, ExternalStorage, GetReactiveKey,
+        ReactiveCell, ReactiveDerived, ReactiveExternal, ReactiveList, Tag,
+    },
+    outputs::{ListOutput, Output, OutputId, PrimitiveOutput, Program, SetOutput},
+    trace::EnteredSpan,
+    Error, Key,
 };
 
+#[cfg(feature = "serde")]
+use super::snapshot::{Persisted, Snapshot};
 use super::{
-    inputs::Inputs, CellId, DerivedId, EvaluationContext, Revision, TypedInputId,
-    TypedInputIdWithKind,
+    inputs::{DynInputs, Inputs, Undo},
+    mode::{Local, Mode, Shared, Storable},
+    CellId, ConstantId, DependencyGraph, DerivedId, DynId, EvaluationContext, Explanation,
+    ExternalId, IdKindFor, ListId, Revision, TypedInputId, TypedInputIdWithKind,
 };
 
-#[derive(Debug, new)]
-pub struct Timeline {
-    #[new(value = "Revision::start()")]
+/// A timeline is `Local` unless it's created with `Timeline::shared`. See `Mode` for the
+/// difference.
+#[derive(Debug)]
+pub struct Timeline<M: Mode = Local> {
     revision: Revision,
-    #[new(default)]
-    inputs: Inputs,
+    inputs: Inputs<M>,
+    program: Program<M>,
 }
 
-impl Default for Timeline {
-    fn default() -> Timeline {
-        Timeline::new()
+impl<M: Mode> Default for Timeline<M> {
+    fn default() -> Timeline<M> {
+        Timeline {
+            revision: Revision::start(),
+            inputs: Inputs::default(),
+            program: Program::default(),
+        }
     }
 }
 
 impl Timeline {
-    pub fn revision<T: Debug + Clone + 'static>(
+    pub fn new() -> Timeline {
+        Timeline::default()
+    }
+}
+
+impl Timeline<Shared> {
+    /// A timeline that only holds values, computations, storage and outputs that are
+    /// `Send + Sync`, so that it can be shared between threads (usually through a
+    /// `SharedTimeline`).
+    pub fn shared() -> Timeline<Shared> {
+        Timeline::default()
+    }
+
+    /// Start rendering on rayon's thread pool.
+    #[cfg(feature = "rayon")]
+    pub fn par_begin(&self) -> ParRenderTransaction<'_> {
+        ParRenderTransaction {
+            revision: self.revision,
+            inputs: &self.inputs,
+        }
+    }
+
+    /// Like `render`, but updates the stale outputs on rayon's thread pool.
+    #[cfg(feature = "rayon")]
+    pub fn par_render(&mut self) -> bool {
+        let transaction = ParRenderTransaction {
+            revision: self.revision,
+            inputs: &self.inputs,
+        };
+        self.program.par_update(&transaction)
+    }
+}
+
+impl<M: Mode> Timeline<M> {
+    pub fn revision<T: 'static>(&self, id: impl Into<TypedInputId<T>>) -> Option<Revision> {
+        self.try_revision(id).ok()
+    }
+
+    /// Like `revision`, but explains why the revision of an input isn't available.
+    pub fn try_revision<T: 'static>(
         &self,
         id: impl Into<TypedInputId<T>>,
-    ) -> Option<Revision> {
+    ) -> Result<Revision, Error> {
         let id = id.into();
         self.inputs.revision(id)
     }
 
-    pub fn output<T: Debug + Clone + 'static>(
+    /// Returns false if the input was deleted.
+    pub fn contains<T: 'static>(&self, id: impl Into<TypedInputId<T>>) -> bool {
+        self.inputs.contains(id)
+    }
+
+    pub fn output<T: 'static>(&self, id: impl Into<TypedInputId<T>>) -> PrimitiveOutput<T> {
+        let id = id.into();
+        PrimitiveOutput::new(None, id)
+    }
+
+    pub fn list_output<T: Debug + Clone + PartialEq + 'static>(
+        &self,
+        id: impl Into<TypedInputId<ReactiveList<T>>>,
+    ) -> ListOutput<T> {
+        ListOutput::new(id.into())
+    }
+
+    pub fn set_output<T, C>(&self, id: impl Into<TypedInputId<C>>) -> SetOutput<T, C>
+    where
+        T: Debug + Clone + Hash + Eq + 'static,
+        C: Debug + Clone + IntoIterator<Item = T> + 'static,
+    {
+        SetOutput::new(id.into())
+    }
+
+    pub fn program(&self) -> &Program<M> {
+        &self.program
+    }
+
+    pub fn program_mut(&mut self) -> &mut Program<M> {
+        &mut self.program
+    }
+
+    /// Hand an output to the timeline's program, so that it's updated by `render`.
+    pub fn register<O: Output + Storable<M>>(&mut self, output: O) -> OutputId<O> {
+        self.program.register(output)
+    }
+
+    /// Take an output back from the timeline's program, so that `render` stops updating it.
+    pub fn unregister<O: Output>(&mut self, id: OutputId<O>) -> O {
+        self.program.unregister(id)
+    }
+
+    /// The inputs of the timeline and the outputs in its program, with the dependencies that were
+    /// recorded the last time each of them was computed.
+    pub fn dependency_graph(&self) -> DependencyGraph {
+        let mut graph = DependencyGraph::default();
+        self.inputs.add_to_graph(&mut graph);
+        self.program.add_to_graph(&mut graph);
+        graph.finish()
+    }
+
+    /// Explain why an input changed since `since`: which cells, lists or external storage changed,
+    /// at which revision, and which derived computations the changes propagated through.
+    pub fn explain<T: 'static>(
+        &self,
+        id: impl Into<TypedInputId<T>>,
+        since: Revision,
+    ) -> Explanation {
+        self.try_explain(id, since)
+            .unwrap_or_else(|error| panic!("{}", error))
+    }
+
+    /// Like `explain`, but returns an error instead of panicking if the input was removed, or if it
+    /// never belonged to this timeline.
+    pub fn try_explain<T: 'static>(
         &self,
         id: impl Into<TypedInputId<T>>,
-    ) -> PrimitiveOutput<T> {
+        since: Revision,
+    ) -> Result<Explanation, Error> {
         let id = id.into();
-        // let value = unimplemented!();
-        PrimitiveOutput::new(None, id)
+        let tag = self.inputs.tag(id)?;
+        Ok(Explanation::new(id.into(), tag, since))
+    }
+
+    /// Save the value of every cell that was persisted under a name. Deleted cells are left out.
+    #[cfg(feature = "serde")]
+    pub fn snapshot(&self) -> Snapshot {
+        self.try_snapshot()
+            .unwrap_or_else(|error| panic!("{}", error))
+    }
+
+    /// Like `snapshot`, but returns an error instead of panicking if a value couldn't be
+    /// serialized.
+    #[cfg(feature = "serde")]
+    pub fn try_snapshot(&self) -> Result<Snapshot, Error> {
+        Snapshot::take(self.inputs.persisted(), &self.inputs)
+    }
+
+    /// Write the values in `snapshot` into the cells that were persisted under the same names, as
+    /// a single batch. Names that the timeline doesn't know about are ignored, and cells that the
+    /// snapshot doesn't have a value for keep their current value. Derived values and outputs are
+    /// recomputed the next time they're read.
+    #[cfg(feature = "serde")]
+    pub fn restore(&mut self, snapshot: &Snapshot) {
+        self.try_restore(snapshot)
+            .unwrap_or_else(|error| panic!("{}", error))
+    }
+
+    /// Like `restore`, but returns an error instead of panicking if a value couldn't be
+    /// deserialized. In that case, none of the cells are written.
+    #[cfg(feature = "serde")]
+    pub fn try_restore(&mut self, snapshot: &Snapshot) -> Result<(), Error> {
+        let restores = snapshot.restores(self.inputs.persisted())?;
+        let mut batch = self.batch();
+
+        for restore in restores {
+            restore(&mut batch);
+        }
+
+        Ok(())
+    }
+
+    /// Returns true if any registered output is stale.
+    pub fn is_stale(&self) -> bool {
+        let transaction = RenderTransaction::new(self.revision, &self.inputs);
+        self.program.is_stale(&transaction)
     }
 
-    pub fn setup(&mut self) -> SetupTransaction<'_> {
+    /// Update every stale output in the timeline's program, and return whether any output changed.
+    pub fn render(&mut self) -> bool {
+        let mut transaction = RenderTransaction::new(self.revision, &self.inputs);
+        self.program.update(&mut transaction)
+    }
+
+    pub fn setup(&mut self) -> SetupTransaction<'_, M> {
         SetupTransaction {
             inputs: &mut self.inputs,
             revision: self.revision,
+            span: span!(DEBUG, "setup", revision = %self.revision).entered(),
         }
     }
 
-    pub fn update(&mut self) -> UpdateTransaction<'_> {
+    pub fn update(&mut self) -> UpdateTransaction<'_, M> {
         UpdateTransaction {
+            span: span!(DEBUG, "update", revision = %self.revision).entered(),
             inputs: &mut self.inputs,
-            revision: self.revision,
+            revision: &mut self.revision,
+            batch: None,
+            journal: None,
+            kept: None,
         }
     }
 
-    pub fn begin(&mut self) -> RenderTransaction<'_> {
-        RenderTransaction {
-            revision: self.revision,
-            ctx: EvaluationContext::new(&self.inputs),
+    /// An update transaction that can be rolled back. Every write records the value and revision
+    /// that it replaced, and the writes are undone unless the transaction is committed.
+    pub fn stage(&mut self) -> StagedTransaction<'_, M> {
+        StagedTransaction {
+            start: self.revision,
+            transaction: UpdateTransaction {
+                span: span!(DEBUG, "stage", revision = %self.revision).entered(),
+                inputs: &mut self.inputs,
+                revision: &mut self.revision,
+                batch: None,
+                journal: Some(vec![]),
+                kept: None,
+            },
+            committed: false,
+        }
+    }
+
+    /// An update transaction whose writes all share a single new revision. The timeline's
+    /// revision advances to it as soon as the first write happens, so later transactions never
+    /// reuse it, whether or not the batch is committed.
+    pub fn batch(&mut self) -> UpdateTransaction<'_, M> {
+        let revision = self.revision.increment();
+
+        UpdateTransaction {
+            span: span!(DEBUG, "batch", revision = %revision).entered(),
+            inputs: &mut self.inputs,
+            revision: &mut self.revision,
+            batch: Some(revision),
+            journal: None,
+            kept: None,
         }
     }
+
+    /// Start rendering. Renders only read the timeline, so several of them can be in progress at
+    /// once.
+    pub fn begin(&self) -> RenderTransaction<'_> {
+        RenderTransaction::new(self.revision, &self.inputs)
+    }
+
+    /// Start rendering the timeline as it was at `revision`. See `value_at` for which inputs can
+    /// be read.
+    ///
+    /// An output doesn't remember the revisions of the inputs it read during a time-travel render,
+    /// so it's stale again afterwards, and the next render brings it back to the present.
+    pub fn begin_at(&self, revision: Revision) -> RenderTransaction<'_> {
+        RenderTransaction::at(revision, &self.inputs)
+    }
+
+    /// The value of an input as it was at `revision`. A cell that was set up with a history
+    /// remembers its past values, as far back as its history goes. Any other input can only be
+    /// read if it didn't change since `revision`. Derived computations are computed from scratch,
+    /// without memoizing anything.
+    pub fn value_at<T: Clone + 'static>(
+        &self,
+        id: impl Into<TypedInputId<T>>,
+        revision: Revision,
+    ) -> T {
+        self.try_value_at(id, revision)
+            .unwrap_or_else(|error| panic!("{}", error))
+    }
+
+    /// Like `value_at`, but returns an error instead of panicking if the input doesn't remember
+    /// its value at `revision`, was removed, or never belonged to this timeline.
+    pub fn try_value_at<T: Clone + 'static>(
+        &self,
+        id: impl Into<TypedInputId<T>>,
+        revision: Revision,
+    ) -> Result<T, Error> {
+        let mut ctx = EvaluationContext::at(&self.inputs, revision);
+        ctx.inputs.value(id, &mut ctx)
+    }
+
+    /// The revisions at which the cells with a history were written, oldest first, as far back as
+    /// their histories go.
+    pub fn updates(&self) -> Vec<Revision> {
+        self.inputs.updates()
+    }
+
+    /// The last update before `revision`, or `None` if the histories don't go back any further.
+    pub fn step_back(&self, revision: Revision) -> Option<Revision> {
+        self.updates()
+            .into_iter()
+            .rev()
+            .find(|update| *update < revision)
+    }
+
+    /// The first update after `revision`, or `None` if `revision` is already the latest update.
+    /// Stepping forward from a revision that `step_back` returned replays the updates in order.
+    pub fn step_forward(&self, revision: Revision) -> Option<Revision> {
+        self.updates().into_iter().find(|update| *update > revision)
+    }
 }
 
-pub struct UpdateTransaction<'a> {
-    inputs: &'a mut Inputs,
-    revision: Revision,
+pub struct UpdateTransaction<'a, M: Mode = Local> {
+    span: EnteredSpan,
+    inputs: &'a mut Inputs<M>,
+    // advances the timeline's revision as cells are updated, so that the next transaction never
+    // reuses a revision
+    revision: &'a mut Revision,
+    // the revision shared by every write, if this transaction is a batch
+    batch: Option<Revision>,
+    // how to undo every write so far, oldest first, if this transaction can be rolled back
+    journal: Option<Vec<Undo<M>>>,
+    // the revision of the last write that a rollback can't undo
+    kept: Option<Revision>,
 }
 
-impl<'a> UpdateTransaction<'a> {
-    pub fn commit(self, timeline: &mut Timeline) {
-        timeline.revision = self.revision
+impl<'a, M: Mode> UpdateTransaction<'a, M> {
+    /// Finish the transaction. Dropping it has the same effect.
+    pub fn commit(self) {}
+
+    pub fn update<T: 'static>(&mut self, id: TypedInputIdWithKind<T, CellId<T>>, value: T) {
+        self.try_update(id, value)
+            .unwrap_or_else(|error| panic!("{}", error))
+    }
+
+    /// Like `update`, but returns an error instead of panicking if the cell was removed, or if it
+    /// never belonged to this timeline. The timeline's revision only advances if the update
+    /// succeeded.
+    pub fn try_update<T: 'static>(
+        &mut self,
+        id: TypedInputIdWithKind<T, CellId<T>>,
+        value: T,
+    ) -> Result<(), Error> {
+        let revision = self.next_revision();
+        let (previous, previous_revision) = self.inputs.update_cell(id, value, revision)?;
+        self.advance(id.into(), revision);
+
+        if let Some(journal) = &mut self.journal {
+            journal.push(Inputs::restore_cell(id, previous, previous_revision));
+        }
+
+        Ok(())
+    }
+
+    /// Update a cell by mutating its value in place, and return the result of `f`. The value has
+    /// to be `Clone`, so that a staged transaction can hold on to the value from before the
+    /// mutation.
+    pub fn modify<T: Clone + 'static, U>(
+        &mut self,
+        id: TypedInputIdWithKind<T, CellId<T>>,
+        f: impl FnOnce(&mut T) -> U,
+    ) -> U {
+        let revision = self.next_revision();
+        self.record(|inputs| inputs.snapshot_cell(id));
+        let result = self
+            .inputs
+            .modify_cell(id, revision, f)
+            .unwrap_or_else(|error| panic!("{}", error));
+        self.advance(id.into(), revision);
+        result
     }
 
-    pub fn update<T: Debug + Clone + 'static>(
+    /// Like `update`, but leaves the cell and its revision alone if `value` is equal to the cell's
+    /// current value. Returns whether the cell changed.
+    pub fn update_if_changed<T: PartialEq + 'static>(
         &mut self,
         id: TypedInputIdWithKind<T, CellId<T>>,
         value: T,
+    ) -> bool {
+        let current = self
+            .inputs
+            .borrow_cell(id)
+            .unwrap_or_else(|error| panic!("{}", error));
+
+        if *current == value {
+            false
+        } else {
+            self.update(id, value);
+            true
+        }
+    }
+
+    /// Record that the value in external storage changed, so that everything that read it is
+    /// invalidated.
+    ///
+    /// The storage already changed outside of the timeline, so rolling back a staged transaction
+    /// doesn't undo this.
+    pub fn dirty<T: 'static>(&mut self, id: TypedInputIdWithKind<T, ExternalId<T>>) {
+        let revision = self.next_revision();
+        self.kept = Some(revision);
+        self.inputs
+            .dirty_external(id, revision)
+            .unwrap_or_else(|error| panic!("{}", error));
+        self.advance(id.into(), revision);
+    }
+
+    /// Append an item to the end of a list.
+    pub fn push<T: Clone + GetReactiveKey + 'static>(
+        &mut self,
+        id: TypedInputIdWithKind<ReactiveList<T>, ListId<ReactiveList<T>>>,
+        item: T,
     ) {
-        let revision = self.increment_revision();
+        self.try_push(id, item)
+            .unwrap_or_else(|error| panic!("{}", error))
+    }
 
-        self.inputs.update_cell(id, value, revision);
+    /// Like `push`, but returns an error instead of panicking if the list already had an item with
+    /// the same key, was removed, or never belonged to this timeline. The timeline's revision only
+    /// advances if the item was pushed.
+    pub fn try_push<T: Clone + GetReactiveKey + 'static>(
+        &mut self,
+        id: TypedInputIdWithKind<ReactiveList<T>, ListId<ReactiveList<T>>>,
+        item: T,
+    ) -> Result<(), Error> {
+        let key = item.get_reactive_key();
+        self.edit_list(id, |list, revision| list.push(key, item, revision))
     }
 
-    fn increment_revision(&mut self) -> Revision {
-        let revision = self.revision.increment();
-        self.revision = revision;
-        revision
+    /// Insert an item into a list at `index`. The item's key must not already be in the list.
+    pub fn insert<T: Clone + GetReactiveKey + 'static>(
+        &mut self,
+        id: TypedInputIdWithKind<ReactiveList<T>, ListId<ReactiveList<T>>>,
+        index: usize,
+        item: T,
+    ) {
+        self.try_insert(id, index, item)
+            .unwrap_or_else(|error| panic!("{}", error))
+    }
+
+    /// Like `insert`, but returns an error instead of panicking if the key was already in the
+    /// list, `index` is past the end of the list, or the list was removed or never belonged to
+    /// this timeline. The timeline's revision only advances if the item was inserted.
+    pub fn try_insert<T: Clone + GetReactiveKey + 'static>(
+        &mut self,
+        id: TypedInputIdWithKind<ReactiveList<T>, ListId<ReactiveList<T>>>,
+        index: usize,
+        item: T,
+    ) -> Result<(), Error> {
+        let key = item.get_reactive_key();
+        self.edit_list(id, |list, revision| list.insert(index, key, item, revision))
+    }
+
+    /// Replace the item in a list that has the same key as `item`, keeping its position.
+    pub fn replace<T: Clone + GetReactiveKey + 'static>(
+        &mut self,
+        id: TypedInputIdWithKind<ReactiveList<T>, ListId<ReactiveList<T>>>,
+        item: T,
+    ) {
+        self.try_replace(id, item)
+            .unwrap_or_else(|error| panic!("{}", error))
+    }
+
+    /// Like `replace`, but returns an error instead of panicking if the list didn't have an item
+    /// with the same key, or was removed or never belonged to this timeline. The timeline's
+    /// revision only advances if the item was replaced.
+    pub fn try_replace<T: Clone + GetReactiveKey + 'static>(
+        &mut self,
+        id: TypedInputIdWithKind<ReactiveList<T>, ListId<ReactiveList<T>>>,
+        item: T,
+    ) -> Result<(), Error> {
+        let key = item.get_reactive_key();
+        self.edit_list(id, |list, revision| list.replace(&key, item, revision))
+    }
+
+    /// Remove the item identified by `key` from a list, and return it.
+    pub fn remove<T: Clone + 'static>(
+        &mut self,
+        id: TypedInputIdWithKind<ReactiveList<T>, ListId<ReactiveList<T>>>,
+        key: &Key,
+    ) -> T {
+        self.try_remove(id, key)
+            .unwrap_or_else(|error| panic!("{}", error))
+    }
+
+    /// Like `remove`, but returns an error instead of panicking if `key` wasn't in the list, or
+    /// the list was removed or never belonged to this timeline. The timeline's revision only
+    /// advances if the item was removed.
+    pub fn try_remove<T: Clone + 'static>(
+        &mut self,
+        id: TypedInputIdWithKind<ReactiveList<T>, ListId<ReactiveList<T>>>,
+        key: &Key,
+    ) -> Result<T, Error> {
+        self.edit_list(id, |list, _| list.remove(key))
+    }
+
+    /// Move the item identified by `key` to `index`, where `index` is the item's position after
+    /// the move.
+    pub fn move_to<T: Clone + 'static>(
+        &mut self,
+        id: TypedInputIdWithKind<ReactiveList<T>, ListId<ReactiveList<T>>>,
+        key: &Key,
+        index: usize,
+    ) {
+        self.try_move_to(id, key, index)
+            .unwrap_or_else(|error| panic!("{}", error))
+    }
+
+    /// Like `move_to`, but returns an error instead of panicking if `key` wasn't in the list,
+    /// `index` is past the end of the list, or the list was removed or never belonged to this
+    /// timeline. The timeline's revision only advances if the item was moved.
+    pub fn try_move_to<T: Clone + 'static>(
+        &mut self,
+        id: TypedInputIdWithKind<ReactiveList<T>, ListId<ReactiveList<T>>>,
+        key: &Key,
+        index: usize,
+    ) -> Result<(), Error> {
+        self.edit_list(id, |list, _| list.move_to(key, index))
+    }
+
+    /// Delete an input, so that the timeline no longer holds on to its value or computation. Ids
+    /// are never reused, so using the id of a deleted input afterwards reliably panics.
+    ///
+    /// Deleting an input doesn't change any revisions. It's up to the caller to make sure that no
+    /// derived computation or output still reads the input.
+    pub fn delete<T: 'static>(&mut self, id: impl Into<TypedInputId<T>>) {
+        let deleted = match &mut self.journal {
+            Some(journal) => match self.inputs.take(id) {
+                Some(undo) => {
+                    journal.push(undo);
+                    true
+                }
+                None => false,
+            },
+            None => self.inputs.delete(id),
+        };
+
+        if !deleted {
+            panic!(
+                "Attempted to delete an input of type {} that was already deleted",
+                std::any::type_name::<T>()
+            );
+        }
+    }
+
+    /// Apply `f` to a list, at the revision of the next write. The list, its revision and the
+    /// timeline's revision are only changed if `f` succeeds, and `f` must leave the list alone
+    /// when it fails.
+    fn edit_list<T: Clone + 'static, U>(
+        &mut self,
+        id: TypedInputIdWithKind<ReactiveList<T>, ListId<ReactiveList<T>>>,
+        f: impl FnOnce(&mut ReactiveList<T>, Revision) -> Result<U, Error>,
+    ) -> Result<U, Error> {
+        let revision = self.next_revision();
+        let undo = match self.journal {
+            Some(_) => Some(self.inputs.snapshot_list(id)?),
+            None => None,
+        };
+
+        let result = self
+            .inputs
+            .update_list(id, revision, |list| f(list, revision))?;
+        self.advance(id.into(), revision);
+
+        if let (Some(journal), Some(undo)) = (&mut self.journal, undo) {
+            journal.push(undo);
+        }
+
+        Ok(result)
+    }
+
+    /// Advance the timeline's revision to `revision`, which `id` was just written at.
+    #[cfg_attr(not(feature = "tracing"), allow(unused_variables))]
+    fn advance(&mut self, id: DynId, revision: Revision) {
+        if *self.revision != revision {
+            event!(DEBUG, id = %id, type_name = id.type_name(), revision = %revision, "revision bump");
+        }
+
+        *self.revision = revision;
+    }
+
+    /// If this transaction can be rolled back, remember how to undo the write that's about to
+    /// happen. An input that can't be snapshotted is about to fail to be written, so there's
+    /// nothing to undo.
+    fn record(&mut self, snapshot: impl FnOnce(&Inputs<M>) -> Result<Undo<M>, Error>) {
+        if let Some(journal) = &mut self.journal {
+            if let Ok(undo) = snapshot(self.inputs) {
+                journal.push(undo);
+            }
+        }
+    }
+
+    /// Undo every write so far, newest first.
+    fn undo(&mut self) {
+        if let Some(journal) = &mut self.journal {
+            while let Some(undo) = journal.pop() {
+                undo(self.inputs);
+            }
+        }
+    }
+
+    /// The revision that the next write will be stamped with. Outside of a batch, every write gets
+    /// its own revision.
+    fn next_revision(&self) -> Revision {
+        match self.batch {
+            Some(revision) => revision,
+            None => self.revision.increment(),
+        }
+    }
+}
+
+/// An update transaction that's rolled back unless it's committed. It derefs to an
+/// `UpdateTransaction`, so it supports the same writes.
+pub struct StagedTransaction<'a, M: Mode = Local> {
+    transaction: UpdateTransaction<'a, M>,
+    // the timeline's revision before the transaction started
+    start: Revision,
+    committed: bool,
+}
+
+impl<'a, M: Mode> StagedTransaction<'a, M> {
+    /// Keep every write.
+    pub fn commit(mut self) {
+        self.committed = true;
+    }
+
+    /// Undo every write, restoring the values and revisions of the inputs as well as the
+    /// timeline's revision. Dropping the transaction without committing it has the same effect.
+    /// Dirtying external storage can't be undone, so it's kept.
+    pub fn rollback(self) {}
+}
+
+impl<'a, M: Mode> Deref for StagedTransaction<'a, M> {
+    type Target = UpdateTransaction<'a, M>;
+
+    fn deref(&self) -> &UpdateTransaction<'a, M> {
+        &self.transaction
+    }
+}
+
+impl<'a, M: Mode> DerefMut for StagedTransaction<'a, M> {
+    fn deref_mut(&mut self) -> &mut UpdateTransaction<'a, M> {
+        &mut self.transaction
+    }
+}
+
+impl<'a, M: Mode> Drop for StagedTransaction<'a, M> {
+    fn drop(&mut self) {
+        if !self.committed {
+            self.transaction.undo();
+            // nothing observed the revisions that the rolled back writes used, so they can be
+            // handed out again, unless they came before a dirty that was kept
+            *self.transaction.revision = self.transaction.kept.unwrap_or(self.start);
+        }
     }
 }
 
 #[derive(Debug)]
 pub struct RenderTransaction<'a> {
+    span: EnteredSpan,
     ctx: EvaluationContext<'a>,
     // does not change during render
     revision: Revision,
 }
 
 impl<'a> RenderTransaction<'a> {
+    fn new(revision: Revision, inputs: &'a dyn DynInputs) -> RenderTransaction<'a> {
+        RenderTransaction {
+            span: span!(DEBUG, "render", revision = %revision).entered(),
+            revision,
+            ctx: EvaluationContext::new(inputs),
+        }
+    }
+
+    fn at(revision: Revision, inputs: &'a dyn DynInputs) -> RenderTransaction<'a> {
+        RenderTransaction {
+            span: span!(DEBUG, "render_at", revision = %revision).entered(),
+            revision,
+            ctx: EvaluationContext::at(inputs, revision),
+        }
+    }
+
     pub fn commit(self) {}
 
+    /// The revision of the timeline when the render began. Every input that the render reads is
+    /// up to date as of this revision.
+    pub fn current_revision(&self) -> Revision {
+        self.revision
+    }
+
     fn increment_revision(&mut self) -> Revision {
         let revision = self.revision.increment();
         self.revision = revision;
@@ -111,35 +712,223 @@ impl<'a> RenderTransaction<'a> {
 
     pub(crate) fn value<T>(&mut self, id: TypedInputId<T>) -> T
     where
-        T: Debug + Clone + 'static,
+        T: Clone + 'static,
+    {
+        let inputs = self.ctx.inputs;
+
+        inputs
+            .value(id, &mut self.ctx)
+            .unwrap_or_else(|error| panic!("{}", error))
+    }
+
+    /// The current revision of an input, as of the last time it was computed. This never computes
+    /// the input. A time-travel render doesn't know the revisions of the past values, so it
+    /// returns `None`, and so does an input that was removed or that never belonged to the
+    /// timeline.
+    pub(crate) fn revision<T>(&self, id: TypedInputId<T>) -> Option<Revision>
+    where
+        T: 'static,
     {
-        self.ctx.inputs.value(id, &mut self.ctx)
+        if self.ctx.as_of().is_some() {
+            return None;
+        }
+
+        self.ctx.inputs.revision(id).ok()
+    }
+
+    /// Like `Timeline::explain`, but for the inputs that this render reads from. This works for a
+    /// render of any timeline, so outputs use it to explain themselves.
+    pub fn explain<T: 'static>(
+        &self,
+        id: impl Into<TypedInputId<T>>,
+        since: Revision,
+    ) -> Explanation {
+        self.try_explain(id, since)
+            .unwrap_or_else(|error| panic!("{}", error))
+    }
+
+    /// Like `explain`, but returns an error instead of panicking if the input was removed, or if it
+    /// never belonged to the timeline.
+    pub fn try_explain<T: 'static>(
+        &self,
+        id: impl Into<TypedInputId<T>>,
+        since: Revision,
+    ) -> Result<Explanation, Error> {
+        self.ctx.inputs.dyn_explain(id.into().into(), since)
+    }
+}
+
+/// A render of a `Timeline<Shared>` on rayon's thread pool. Every worker thread renders with a
+/// `RenderTransaction` of its own, at the revision of the timeline when the render began.
+#[cfg(feature = "rayon")]
+#[derive(Debug, Clone, Copy)]
+pub struct ParRenderTransaction<'a> {
+    revision: Revision,
+    inputs: &'a Inputs<Shared>,
+}
+
+#[cfg(feature = "rayon")]
+impl<'a> ParRenderTransaction<'a> {
+    /// A render transaction for the current worker thread.
+    pub(crate) fn begin(&self) -> RenderTransaction<'a> {
+        RenderTransaction::new(self.revision, self.inputs)
+    }
+
+    /// Update every stale output in `outputs` on rayon's thread pool, and return whether any output
+    /// changed. Every output observes the same revision that a sequential render would.
+    pub fn par_update<O: Output + Send>(&self, outputs: &mut [O]) -> bool {
+        use rayon::prelude::*;
+
+        outputs
+            .par_iter_mut()
+            .map_init(
+                || self.begin(),
+                |transaction, output| output.update(transaction),
+            )
+            .reduce(|| false, |left, right| left || right)
     }
 }
 
 #[derive(Debug)]
-pub struct SetupTransaction<'a> {
-    inputs: &'a mut Inputs,
+pub struct SetupTransaction<'a, M: Mode = Local> {
+    inputs: &'a mut Inputs<M>,
     // does not change during setup
     revision: Revision,
+    span: EnteredSpan,
 }
 
-impl<'a> SetupTransaction<'a> {
+impl<'a, M: Mode> SetupTransaction<'a, M> {
     pub fn commit(self) {}
 
-    pub fn cell<T: Debug + Clone + 'static>(
+    pub fn cell<T: Storable<M> + 'static>(
         &mut self,
         value: T,
     ) -> TypedInputIdWithKind<T, CellId<T>> {
         let cell = ReactiveCell::new(value, Tag::arc(self.revision.atomic()));
-        self.inputs.add_cell::<T>(cell)
+        let id = self.inputs.add_cell::<T>(cell);
+        self.added(id)
+    }
+
+    /// Like `cell`, but the cell remembers up to `depth` of the values that it held before its
+    /// current value, so that `Timeline::value_at` can read them.
+    pub fn cell_with_history<T: Clone + Storable<M> + 'static>(
+        &mut self,
+        value: T,
+        depth: usize,
+    ) -> TypedInputIdWithKind<T, CellId<T>> {
+        let cell = ReactiveCell::with_history(value, Tag::arc(self.revision.atomic()), depth);
+        let id = self.inputs.add_cell::<T>(cell);
+        self.added(id)
+    }
+
+    /// Give a cell a name that stays the same across sessions, so that `Timeline::snapshot` saves
+    /// its value and `Timeline::restore` writes it back.
+    #[cfg(feature = "serde")]
+    pub fn persist<T>(&mut self, name: impl Into<String>, id: TypedInputIdWithKind<T, CellId<T>>)
+    where
+        T: serde::Serialize + serde::de::DeserializeOwned + 'static,
+    {
+        self.try_persist(name, id)
+            .unwrap_or_else(|error| panic!("{}", error))
+    }
+
+    /// Like `persist`, but returns an error instead of panicking if another cell was already
+    /// persisted under the same name.
+    #[cfg(feature = "serde")]
+    pub fn try_persist<T>(
+        &mut self,
+        name: impl Into<String>,
+        id: TypedInputIdWithKind<T, CellId<T>>,
+    ) -> Result<(), Error>
+    where
+        T: serde::Serialize + serde::de::DeserializeOwned + 'static,
+    {
+        let name = name.into();
+        let persisted = Persisted::new(id);
+        self.inputs.persist(name, persisted)
+    }
+
+    /// A value that never changes. Since the returned id isn't a `CellId`, it can't be passed to
+    /// `UpdateTransaction::update`.
+    pub fn constant<T: Storable<M> + 'static>(
+        &mut self,
+        value: T,
+    ) -> TypedInputIdWithKind<T, ConstantId<T>> {
+        let constant = ReactiveCell::new(value, Tag::arc(Revision::constant().atomic()));
+        let id = self.inputs.add_constant::<T>(constant);
+        self.added(id)
+    }
+
+    /// A list of keyed items. The list can be changed item by item through an
+    /// `UpdateTransaction`, and reading it through an `EvaluationContext` produces a
+    /// `ReactiveList`.
+    pub fn list<T: Storable<M> + 'static>(
+        &mut self,
+        items: impl CopyIntoReactiveList<T>,
+    ) -> TypedInputIdWithKind<ReactiveList<T>, ListId<ReactiveList<T>>> {
+        let mut list = items.copy_into_reactive_list();
+        list.stamp(self.revision);
+
+        let list = ReactiveCell::new(list, Tag::arc(self.revision.atomic()));
+        let id = self.inputs.add_list::<T>(list);
+        self.added(id)
+    }
+
+    /// Storage that lives outside of the timeline. Reading the returned id reads the storage
+    /// directly, and the storage is considered unchanged until it's dirtied through
+    /// `UpdateTransaction::dirty`.
+    pub fn external<T: Storable<M> + 'static>(
+        &mut self,
+        storage: impl ExternalStorage<T> + Storable<M> + 'static,
+    ) -> TypedInputIdWithKind<T, ExternalId<T>> {
+        let external = ReactiveExternal::new(storage.into_boxed_storage(), self.revision);
+        let id = self.inputs.add_external::<T>(external);
+        self.added(id)
+    }
+
+    pub fn derived<T: Storable<M> + 'static>(
+        &mut self,
+        computation: impl DynamicComputation<T> + Storable<M> + 'static,
+    ) -> TypedInputIdWithKind<T, DerivedId<T>> {
+        let derived =
+            ReactiveDerived::new(DerivedTag::default(), computation.into_boxed_computation());
+        let id = self.inputs.add_derived::<T>(derived);
+        self.added(id)
     }
 
-    pub fn derived<T: Debug + Clone + 'static>(
+    /// A derived computation that remembers its last value, and only recomputes it once one of
+    /// the dependencies of the last computation changed.
+    pub fn memo<T: Clone + Storable<M> + 'static>(
         &mut self,
-        computation: impl DynamicComputation<T> + 'static,
+        computation: impl DynamicComputation<T> + Storable<M> + 'static,
     ) -> TypedInputIdWithKind<T, DerivedId<T>> {
-        let derived = ReactiveDerived::new(DerivedTag::default(), Box::new(computation));
-        self.inputs.add_derived::<T>(derived)
+        let derived =
+            ReactiveDerived::memo(DerivedTag::default(), computation.into_boxed_computation());
+        let id = self.inputs.add_derived::<T>(derived);
+        self.added(id)
+    }
+
+    /// Like `memo`, but when a recomputation produces a value equal to the memoized one, the memo
+    /// keeps the revision of the old value. Derived computations and outputs that read the memo
+    /// then stay valid.
+    pub fn backdated_memo<T: Clone + PartialEq + Storable<M> + 'static>(
+        &mut self,
+        computation: impl DynamicComputation<T> + Storable<M> + 'static,
+    ) -> TypedInputIdWithKind<T, DerivedId<T>> {
+        let derived = ReactiveDerived::backdated_memo(
+            DerivedTag::default(),
+            computation.into_boxed_computation(),
+        );
+        let id = self.inputs.add_derived::<T>(derived);
+        self.added(id)
+    }
+
+    fn added<T: 'static, K: IdKindFor<T>>(
+        &self,
+        id: TypedInputIdWithKind<T, K>,
+    ) -> TypedInputIdWithKind<T, K> {
+        event!(DEBUG, id = %DynId::from(id), type_name = std::any::type_name::<T>(), "added");
+
+        id
     }
 }
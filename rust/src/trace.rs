@@ -0,0 +1,45 @@
+//! Spans and events for the `tracing` feature. Without the feature, `span!` produces a `Span` that
+//! doesn't do anything, and `event!` expands to nothing, so the fields are never evaluated.
+
+#[cfg(feature = "tracing")]
+pub(crate) use tracing::span::EnteredSpan;
+
+#[cfg(not(feature = "tracing"))]
+#[derive(Debug, Clone)]
+pub(crate) struct Span;
+
+#[cfg(not(feature = "tracing"))]
+impl Span {
+    pub(crate) fn in_scope<T>(&self, f: impl FnOnce() -> T) -> T {
+        f()
+    }
+
+    pub(crate) fn entered(self) -> EnteredSpan {
+        EnteredSpan
+    }
+}
+
+/// A span that stays entered until it's dropped. Transactions hold one, so that everything that
+/// happens while a transaction is open is recorded inside of its span.
+#[cfg(not(feature = "tracing"))]
+#[derive(Debug)]
+pub(crate) struct EnteredSpan;
+
+/// `span!(DEBUG, "name", field = value, ...)`
+macro_rules! span {
+    ($level:ident, $($args:tt)*) => {{
+        #[cfg(feature = "tracing")]
+        let span = tracing::span!(tracing::Level::$level, $($args)*);
+        #[cfg(not(feature = "tracing"))]
+        let span = $crate::trace::Span;
+        span
+    }};
+}
+
+/// `event!(TRACE, field = value, ..., "message")`
+macro_rules! event {
+    ($level:ident, $($args:tt)*) => {
+        #[cfg(feature = "tracing")]
+        tracing::event!(tracing::Level::$level, $($args)*);
+    };
+}
@@ -0,0 +1,72 @@
+use std::{cell::Cell, rc::Rc};
+
+use everafter::timeline::{EvaluationContext, Timeline};
+
+#[test]
+fn batch_shares_one_revision() {
+    let mut timeline = Timeline::new();
+
+    let mut transaction = timeline.setup();
+    let first = transaction.cell("Matt".to_string());
+    let last = transaction.cell("Hammer".to_string());
+    let untouched = transaction.cell("Hello".to_string());
+
+    let mut transaction = timeline.batch();
+    transaction.update(first, "Godfrey".to_string());
+    transaction.update(last, "Chan".to_string());
+    transaction.update(first, "Yehuda".to_string());
+    transaction.commit();
+
+    let batched = timeline.revision(first).unwrap();
+    assert_eq!(timeline.revision(last), Some(batched));
+    assert!(batched > timeline.revision(untouched).unwrap());
+
+    // the next write gets a new revision, even though the batch was dropped without being
+    // committed
+    {
+        let mut transaction = timeline.batch();
+        transaction.update(last, "Katz".to_string());
+    }
+
+    let next = timeline.revision(last).unwrap();
+    assert!(next > batched);
+
+    timeline.update().update(first, "Tom".to_string());
+    assert!(timeline.revision(first).unwrap() > next);
+}
+
+#[test]
+fn batch_recomputes_once() {
+    let mut timeline = Timeline::new();
+    let computed = Rc::new(Cell::new(0));
+
+    let mut transaction = timeline.setup();
+    let first = transaction.cell("Matt".to_string());
+    let last = transaction.cell("Hammer".to_string());
+    let full_name = {
+        let computed = computed.clone();
+        transaction.memo(move |ctx: &mut EvaluationContext| {
+            computed.set(computed.get() + 1);
+            format!("{} {}", ctx.value(first), ctx.value(last))
+        })
+    };
+
+    let mut output = timeline.output(full_name);
+    let mut transaction = timeline.begin();
+    output.update(&mut transaction);
+    assert_eq!(computed.get(), 1);
+
+    let mut transaction = timeline.batch();
+    transaction.update(first, "Yehuda".to_string());
+    transaction.update(last, "Katz".to_string());
+    transaction.commit();
+
+    let mut transaction = timeline.begin();
+    assert!(output.update(&mut transaction));
+    assert_eq!(output.value(), "Yehuda Katz");
+    assert_eq!(computed.get(), 2);
+
+    let mut transaction = timeline.begin();
+    assert!(!output.update(&mut transaction));
+    assert_eq!(computed.get(), 2);
+}
@@ -1,12 +1,15 @@
-use std::{fmt::Debug, marker::PhantomData};
+use std::{borrow::Borrow, fmt::Debug, marker::PhantomData};
 
 use derive_new::new;
 use getset::Getters;
 
 use everafter::{
-    inputs::DynamicComputation,
-    outputs::PrimitiveOutput,
-    timeline::{CellId, DerivedId, IdKindFor, RenderTransaction, Timeline, TypedInputIdWithKind},
+    inputs::{CopyIntoReactiveList, DynamicComputation, ReactiveList},
+    outputs::{ListOutput, PrimitiveOutput},
+    timeline::{
+        CellId, ConstantId, DerivedId, IdKindFor, ListId, RenderTransaction, Timeline,
+        TypedInputIdWithKind, UpdateTransaction,
+    },
     Revision, TypedInputId,
 };
 
@@ -22,6 +25,10 @@ impl Test {
         self.timeline.begin()
     }
 
+    pub fn edit(&mut self) -> UpdateTransaction<'_> {
+        self.timeline.update()
+    }
+
     pub fn cell<T: Debug + Clone + 'static>(
         &mut self,
         desc: &'static str,
@@ -35,13 +42,45 @@ impl Test {
             .expect("cell unexpectedly initialized with a None revision");
 
         TestReactive {
-            desc: desc,
+            desc,
             handle: cell,
             marker: PhantomData,
             last_revision: Some(revision),
         }
     }
 
+    pub fn constant<T: Debug + Clone + 'static>(
+        &mut self,
+        desc: &'static str,
+        value: T,
+    ) -> TestReactive<T, ConstantId<T>> {
+        let mut timeline = self.timeline.setup();
+        let constant = timeline.constant(value);
+
+        TestReactive {
+            desc,
+            handle: constant,
+            marker: PhantomData,
+            last_revision: self.timeline.revision(constant),
+        }
+    }
+
+    pub fn list<T: Debug + Clone + 'static>(
+        &mut self,
+        desc: &'static str,
+        items: impl CopyIntoReactiveList<T>,
+    ) -> TestReactive<ReactiveList<T>, ListId<ReactiveList<T>>> {
+        let mut timeline = self.timeline.setup();
+        let list = timeline.list(items);
+
+        TestReactive {
+            desc,
+            handle: list,
+            marker: PhantomData,
+            last_revision: self.timeline.revision(list),
+        }
+    }
+
     pub fn derived<T: Debug + Clone + 'static>(
         &mut self,
         desc: &'static str,
@@ -51,18 +90,38 @@ impl Test {
         let derived = timeline.derived(computation);
 
         TestReactive {
-            desc: desc,
+            desc,
             handle: derived,
             marker: PhantomData,
             last_revision: None,
         }
     }
 
-    pub fn assert_unchanged<T, K>(&self, reactive: &TestReactive<T, K>, desc: &'static str)
-    where
+    pub fn memo<T: Debug + Clone + 'static>(
+        &mut self,
+        desc: &'static str,
+        computation: impl DynamicComputation<T> + 'static,
+    ) -> TestReactive<T, DerivedId<T>> {
+        let mut timeline = self.timeline.setup();
+        let memo = timeline.memo(computation);
+
+        TestReactive {
+            desc,
+            handle: memo,
+            marker: PhantomData,
+            last_revision: None,
+        }
+    }
+
+    pub fn assert_unchanged<T, K>(
+        &self,
+        reactive: impl Borrow<TestReactive<T, K>>,
+        desc: &'static str,
+    ) where
         T: Debug + Clone + 'static,
         K: IdKindFor<T>,
     {
+        let reactive = reactive.borrow();
         let revision = self.timeline.revision(reactive.handle);
 
         assert_eq!(
@@ -110,21 +169,21 @@ where
         self.handle
     }
 
-    pub fn output(&self, desc: &'static str, test: &Test) -> TestPrimitiveOutput<T> {
-        return TestPrimitiveOutput {
+    pub fn output(&self, desc: &'static str, test: impl Borrow<Test>) -> TestPrimitiveOutput<T> {
+        TestPrimitiveOutput {
             desc,
-            output: test.timeline.output(self.handle),
-        };
+            output: test.borrow().timeline.output(self.handle),
+        }
     }
 }
 
-impl<T, K> Into<TypedInputId<T>> for &TestReactive<T, K>
+impl<T, K> From<&TestReactive<T, K>> for TypedInputId<T>
 where
     T: Debug + Clone + PartialEq + 'static,
     K: IdKindFor<T>,
 {
-    fn into(self) -> TypedInputId<T> {
-        self.handle().into()
+    fn from(reactive: &TestReactive<T, K>) -> TypedInputId<T> {
+        reactive.handle().into()
     }
 }
 
@@ -146,9 +205,20 @@ where
         assert_eq!(actual, expected.into(), "{}: {}", self.desc, reason)
     }
 
-    pub fn update(&mut self, test: &mut RenderTransaction<'_>) {
-        // test.timeline.begin().update(id, value)
-        self.output.update(test);
+    pub fn update(&mut self, test: &mut RenderTransaction<'_>) -> bool {
+        self.output.update(test)
+    }
+
+    pub fn is_stale(&self, test: &RenderTransaction<'_>) -> bool {
+        self.output.is_stale(test)
+    }
+
+    pub fn is_constant(&self) -> bool {
+        self.output.is_constant()
+    }
+
+    pub fn value(&self) -> T {
+        self.output.value()
     }
 }
 
@@ -160,3 +230,13 @@ where
         test.timeline.update().update(self.handle, value);
     }
 }
+
+impl<T, K> TestReactive<ReactiveList<T>, K>
+where
+    T: Debug + Clone + PartialEq + 'static,
+    K: IdKindFor<ReactiveList<T>>,
+{
+    pub fn list_output(&self, test: &Test) -> ListOutput<T> {
+        test.timeline.list_output(self.handle)
+    }
+}
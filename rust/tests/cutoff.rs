@@ -0,0 +1,110 @@
+use std::{cell::Cell, rc::Rc};
+
+use everafter::timeline::{EvaluationContext, Timeline};
+
+#[test]
+fn update_if_changed() {
+    let mut timeline = Timeline::new();
+
+    let mut transaction = timeline.setup();
+    let name = transaction.cell("Matt".to_string());
+    let mut output = timeline.output(name);
+
+    let mut transaction = timeline.begin();
+    output.update(&mut transaction);
+
+    let revision = timeline.revision(name);
+
+    let mut transaction = timeline.update();
+    assert!(!transaction.update_if_changed(name, "Matt".to_string()));
+    assert_eq!(timeline.revision(name), revision);
+
+    let mut transaction = timeline.begin();
+    assert!(!output.update(&mut transaction));
+
+    let mut transaction = timeline.update();
+    assert!(transaction.update_if_changed(name, "Godfrey".to_string()));
+    assert!(timeline.revision(name) > revision);
+
+    let mut transaction = timeline.begin();
+    assert!(output.update(&mut transaction));
+    assert_eq!(output.value(), "Godfrey");
+}
+
+#[test]
+fn backdated_memo() {
+    let mut timeline = Timeline::new();
+    let parity_computed = Rc::new(Cell::new(0));
+    let label_computed = Rc::new(Cell::new(0));
+
+    let mut transaction = timeline.setup();
+    let number = transaction.cell(2u32);
+    let parity = {
+        let computed = parity_computed.clone();
+        transaction.backdated_memo(move |ctx: &mut EvaluationContext| {
+            computed.set(computed.get() + 1);
+            ctx.value(number).is_multiple_of(2)
+        })
+    };
+    let label = {
+        let computed = label_computed.clone();
+        transaction.memo(move |ctx: &mut EvaluationContext| {
+            computed.set(computed.get() + 1);
+            if ctx.value(parity) { "even" } else { "odd" }.to_string()
+        })
+    };
+
+    let mut output = timeline.output(label);
+    let mut transaction = timeline.begin();
+    output.update(&mut transaction);
+    assert_eq!(output.value(), "even");
+    assert_eq!((parity_computed.get(), label_computed.get()), (1, 1));
+
+    // the parity is recomputed, but it didn't change, so nothing downstream is
+    timeline.update().update(number, 4);
+
+    let mut transaction = timeline.begin();
+    assert!(!output.update(&mut transaction));
+    assert_eq!((parity_computed.get(), label_computed.get()), (2, 1));
+
+    // nothing changed since the last recomputation, so the parity is still memoized
+    let mut transaction = timeline.begin();
+    assert!(!output.update(&mut transaction));
+    assert_eq!((parity_computed.get(), label_computed.get()), (2, 1));
+
+    timeline.update().update(number, 5);
+
+    let mut transaction = timeline.begin();
+    assert!(output.update(&mut transaction));
+    assert_eq!(output.value(), "odd");
+    assert_eq!((parity_computed.get(), label_computed.get()), (3, 2));
+}
+
+#[test]
+fn memo_without_backdating() {
+    let mut timeline = Timeline::new();
+    let label_computed = Rc::new(Cell::new(0));
+
+    let mut transaction = timeline.setup();
+    let number = transaction.cell(2u32);
+    let parity =
+        transaction.memo(move |ctx: &mut EvaluationContext| ctx.value(number).is_multiple_of(2));
+    let label = {
+        let computed = label_computed.clone();
+        transaction.memo(move |ctx: &mut EvaluationContext| {
+            computed.set(computed.get() + 1);
+            if ctx.value(parity) { "even" } else { "odd" }.to_string()
+        })
+    };
+
+    let mut output = timeline.output(label);
+    let mut transaction = timeline.begin();
+    output.update(&mut transaction);
+
+    timeline.update().update(number, 4);
+
+    let mut transaction = timeline.begin();
+    output.update(&mut transaction);
+    assert_eq!(output.value(), "even");
+    assert_eq!(label_computed.get(), 2);
+}
@@ -0,0 +1,79 @@
+use std::{cell::Cell, rc::Rc};
+
+use everafter::{
+    timeline::{DerivedId, DynId, EvaluationContext, Timeline, TypedInputIdWithKind},
+    Error,
+};
+
+type Slot = Rc<Cell<Option<TypedInputIdWithKind<String, DerivedId<String>>>>>;
+
+#[test]
+fn cycle_path() {
+    let mut timeline = Timeline::new();
+
+    // `a` reads `b` and `b` reads `a`, but `a` has to exist before `b` can refer to it, so `a`
+    // finds `b` through a slot
+    let slot: Slot = Rc::new(Cell::new(None));
+    let errors = Rc::new(Cell::new(None));
+
+    let mut transaction = timeline.setup();
+    let a = {
+        let slot = slot.clone();
+        transaction.derived(move |ctx: &mut EvaluationContext| {
+            let b = slot.get().unwrap();
+            format!("a({})", ctx.value(b))
+        })
+    };
+    let b = {
+        let errors = errors.clone();
+        transaction.derived(move |ctx: &mut EvaluationContext| match ctx.try_value(a) {
+            Ok(a) => format!("b({})", a),
+            Err(error) => {
+                errors.set(Some(error));
+                "b".to_string()
+            }
+        })
+    };
+    slot.set(Some(b));
+
+    let mut output = timeline.output(a);
+    let mut transaction = timeline.begin();
+    output.update(&mut transaction);
+    assert_eq!(output.value(), "a(b)");
+
+    let error: Error = errors.take().unwrap();
+    assert_eq!(
+        error,
+        Error::Cycle {
+            path: vec![DynId::from(a), DynId::from(b), DynId::from(a)]
+        }
+    );
+    assert_eq!(
+        error.to_string(),
+        format!(
+            "cycle detected while computing derived values: {} -> {} -> {}",
+            DynId::from(a),
+            DynId::from(b),
+            DynId::from(a)
+        )
+    );
+}
+
+#[test]
+#[should_panic(expected = "cycle detected")]
+fn self_cycle() {
+    let mut timeline = Timeline::new();
+
+    let slot: Slot = Rc::new(Cell::new(None));
+
+    let mut transaction = timeline.setup();
+    let a = {
+        let slot = slot.clone();
+        transaction.memo(move |ctx: &mut EvaluationContext| ctx.value(slot.get().unwrap()))
+    };
+    slot.set(Some(a));
+
+    let mut output = timeline.output(a);
+    let mut transaction = timeline.begin();
+    output.update(&mut transaction);
+}
@@ -0,0 +1,84 @@
+use everafter::timeline::{EvaluationContext, Timeline};
+
+#[test]
+fn delete_inputs() {
+    let mut timeline = Timeline::new();
+
+    let mut transaction = timeline.setup();
+    let first = transaction.cell("Matt".to_string());
+    let last = transaction.cell("Hammer".to_string());
+    let full_name = transaction.derived(move |ctx: &mut EvaluationContext| {
+        format!("{} {}", ctx.value(first), ctx.value(last))
+    });
+    let greeting = transaction.constant("hello".to_string());
+
+    let mut output = timeline.output(full_name);
+    let mut transaction = timeline.begin();
+    output.update(&mut transaction);
+    assert_eq!(output.value(), "Matt Hammer");
+
+    // the output is gone, so everything it used can be deleted
+    drop(output);
+
+    let mut transaction = timeline.update();
+    transaction.delete(full_name);
+    transaction.delete(first);
+    transaction.delete(last);
+    transaction.delete(greeting);
+
+    assert!(!timeline.contains(full_name));
+    assert!(!timeline.contains(first));
+    assert!(!timeline.contains(last));
+    assert!(!timeline.contains(greeting));
+
+    // new inputs get fresh ids
+    let mut transaction = timeline.setup();
+    let name = transaction.cell("Matthew".to_string());
+
+    assert!(timeline.contains(name));
+    assert_ne!(name, first);
+}
+
+#[test]
+#[should_panic(expected = "was removed")]
+fn read_deleted_input() {
+    let mut timeline = Timeline::new();
+
+    let mut transaction = timeline.setup();
+    let name = transaction.cell("Matt".to_string());
+    let mut output = timeline.output(name);
+
+    timeline.update().delete(name);
+
+    let mut transaction = timeline.begin();
+    output.update(&mut transaction);
+}
+
+#[test]
+fn deleted_input_is_stale() {
+    let mut timeline = Timeline::new();
+
+    let mut transaction = timeline.setup();
+    let name = transaction.cell("Matt".to_string());
+    let mut output = timeline.output(name);
+    output.update(&mut timeline.begin());
+    assert!(!output.is_stale(&timeline.begin()));
+
+    timeline.update().delete(name);
+
+    // checking whether the output is stale doesn't panic, only reading the input again does
+    assert!(output.is_stale(&timeline.begin()));
+}
+
+#[test]
+#[should_panic(expected = "already deleted")]
+fn delete_twice() {
+    let mut timeline = Timeline::new();
+
+    let mut transaction = timeline.setup();
+    let name = transaction.cell("Matt".to_string());
+
+    let mut transaction = timeline.update();
+    transaction.delete(name);
+    transaction.delete(name);
+}
@@ -0,0 +1,138 @@
+use everafter::{
+    timeline::{DynId, EvaluationContext, IdKind, Timeline},
+    Error,
+};
+
+#[test]
+fn try_revision() {
+    let mut timeline = Timeline::new();
+
+    let mut transaction = timeline.setup();
+    let name = transaction.cell("Matt".to_string());
+    let revision = timeline.revision(name).unwrap();
+
+    assert_eq!(timeline.try_revision(name), Ok(revision));
+
+    timeline.update().delete(name);
+
+    assert_eq!(
+        timeline.try_revision(name),
+        Err(Error::Removed { id: name.into() })
+    );
+    assert_eq!(timeline.revision(name), None);
+}
+
+#[test]
+fn unknown_handles() {
+    let mut other = Timeline::new();
+
+    let mut transaction = other.setup();
+    transaction.cell("Matt".to_string());
+    let foreign_name = transaction.cell("Hammer".to_string());
+    let foreign_age = transaction.cell(34u32);
+
+    let mut timeline = Timeline::new();
+    let mut transaction = timeline.setup();
+    transaction.cell("Godfrey".to_string());
+
+    // the timeline has a map for strings, but not one with this id
+    assert_eq!(
+        timeline.try_revision(foreign_name),
+        Err(Error::UnknownId {
+            id: foreign_name.into()
+        })
+    );
+
+    // the timeline doesn't have any numbers at all
+    assert_eq!(
+        timeline.try_revision(foreign_age),
+        Err(Error::UnknownType { type_name: "u32" })
+    );
+
+    let mut transaction = timeline.update();
+    let error = transaction.try_update(foreign_age, 35).unwrap_err();
+    assert_eq!(error, Error::UnknownType { type_name: "u32" });
+}
+
+#[test]
+fn try_update_removed() {
+    let mut timeline = Timeline::new();
+    let mut reference = Timeline::new();
+
+    let mut transaction = timeline.setup();
+    let name = transaction.cell("Matt".to_string());
+    let other = transaction.cell("Hammer".to_string());
+    let reference_other = reference.setup().cell("Hammer".to_string());
+
+    timeline.update().delete(name);
+
+    let mut transaction = timeline.update();
+    let error = transaction
+        .try_update(name, "Matthew".to_string())
+        .unwrap_err();
+    assert_eq!(error, Error::Removed { id: name.into() });
+    assert_eq!(
+        error.to_string(),
+        format!("{} was removed", DynId::from(name))
+    );
+
+    // a failed update doesn't advance the timeline
+    transaction.update(other, "Katz".to_string());
+    reference
+        .update()
+        .update(reference_other, "Katz".to_string());
+    assert_eq!(
+        timeline.revision(other),
+        reference.revision(reference_other)
+    );
+}
+
+#[test]
+fn try_value() {
+    let mut timeline = Timeline::new();
+
+    let mut transaction = timeline.setup();
+    let first = transaction.cell("Matt".to_string());
+    let last = transaction.cell("Hammer".to_string());
+    let full_name = transaction.derived(move |ctx: &mut EvaluationContext| {
+        match (ctx.try_value(first), ctx.try_value(last)) {
+            (Ok(first), Ok(last)) => format!("{} {}", first, last),
+            (Ok(first), Err(_)) => first,
+            (Err(error), _) => error.to_string(),
+        }
+    });
+
+    let mut output = timeline.output(full_name);
+
+    let mut transaction = timeline.begin();
+    output.update(&mut transaction);
+    assert_eq!(output.value(), "Matt Hammer");
+
+    // deleting an input doesn't invalidate anything by itself
+    let mut transaction = timeline.update();
+    transaction.delete(last);
+    transaction.update(first, "Matthew".to_string());
+
+    let mut transaction = timeline.begin();
+    output.update(&mut transaction);
+    assert_eq!(output.value(), "Matthew");
+}
+
+#[test]
+fn mismatched_dyn_id() {
+    let mut timeline = Timeline::new();
+
+    let mut transaction = timeline.setup();
+    let name = transaction.cell("Matt".to_string());
+    let id = DynId::from(name);
+
+    assert_eq!(id.kind(), IdKind::CellId);
+    assert!(id.try_downcast::<String>().is_ok());
+    assert_eq!(
+        id.try_downcast::<u32>().unwrap_err(),
+        Error::TypeMismatch {
+            expected: "u32",
+            actual: "alloc::string::String"
+        }
+    );
+}
@@ -0,0 +1,128 @@
+use everafter::{
+    outputs::Output,
+    timeline::{Cause, DynId, EvaluationContext, Timeline},
+};
+
+#[test]
+fn explain_derived_chain() {
+    let mut timeline = Timeline::new();
+
+    let mut transaction = timeline.setup();
+    let first = transaction.cell("Matt".to_string());
+    let last = transaction.cell("Hammer".to_string());
+    let full_name = transaction.derived(move |ctx: &mut EvaluationContext| {
+        format!("{} {}", ctx.value(first), ctx.value(last))
+    });
+    let greeting = transaction
+        .memo(move |ctx: &mut EvaluationContext| format!("Hello, {}", ctx.value(full_name)));
+
+    let mut output = timeline.output(greeting);
+    output.update(&mut timeline.begin());
+    let rendered = timeline.revision(greeting).unwrap();
+
+    // nothing changed yet
+    let explanation = timeline.explain(greeting, rendered);
+    assert!(!explanation.is_stale());
+    assert_eq!(explanation.checks().len(), 1);
+    assert!(output.explain(&timeline.begin()).is_empty());
+
+    timeline.update().update(first, "Matthew".to_string());
+    let updated = timeline.revision(first).unwrap();
+
+    let explanation = timeline.explain(greeting, rendered);
+    assert!(explanation.is_stale());
+    assert_eq!(
+        explanation.causes(),
+        &vec![Cause {
+            source: first.into(),
+            revision: updated,
+            path: vec![first.into(), full_name.into(), greeting.into()],
+        }]
+    );
+
+    let checks: Vec<_> = explanation
+        .checks()
+        .iter()
+        .map(|check| (check.id, check.is_up_to_date()))
+        .collect();
+    assert_eq!(
+        checks,
+        vec![
+            (DynId::from(greeting), false),
+            (DynId::from(full_name), false),
+            (DynId::from(first), false),
+            (DynId::from(last), true),
+        ]
+    );
+
+    assert_eq!(
+        explanation.to_string(),
+        format!(
+            "Is {} up to date for «1»? no\n\
+             Is {} up to date for «1»? no\n\
+             Is {} up to date for «1»? no\n\
+             Is {} up to date for «1»? yes\n",
+            DynId::from(greeting),
+            DynId::from(full_name),
+            DynId::from(first),
+            DynId::from(last)
+        )
+    );
+
+    // the output explains the same change
+    assert_eq!(output.explain(&timeline.begin()), vec![explanation]);
+
+    output.update(&mut timeline.begin());
+    assert!(output.explain(&timeline.begin()).is_empty());
+}
+
+#[test]
+fn explain_backdated_memo() {
+    let mut timeline = Timeline::new();
+
+    let mut transaction = timeline.setup();
+    let count = transaction.cell(1u32);
+    let parity =
+        transaction.backdated_memo(move |ctx: &mut EvaluationContext| ctx.value(count) % 2);
+    let label = transaction
+        .memo(move |ctx: &mut EvaluationContext| format!("parity: {}", ctx.value(parity)));
+
+    let mut output = timeline.output(label);
+    output.update(&mut timeline.begin());
+    let rendered = timeline.revision(label).unwrap();
+
+    timeline.update().update(count, 3);
+
+    // until the parity is recomputed, the change to count looks like it reaches the label
+    let explanation = timeline.explain(label, rendered);
+    assert_eq!(
+        explanation.causes()[0].path,
+        vec![count.into(), parity.into(), label.into()]
+    );
+
+    // once it's recomputed, the parity turns out not to have changed, and the chain stops there
+    output.update(&mut timeline.begin());
+    let explanation = timeline.explain(label, rendered);
+    assert!(!explanation.is_stale());
+    assert!(output.explain(&timeline.begin()).is_empty());
+}
+
+#[test]
+fn explain_shared_output() {
+    let mut timeline = Timeline::shared();
+
+    let mut transaction = timeline.setup();
+    let count = transaction.cell(1u32);
+    let doubled = transaction.derived(move |ctx: &mut EvaluationContext| ctx.value(count) * 2);
+
+    let mut output = timeline.output(doubled);
+    output.update(&mut timeline.begin());
+    let rendered = timeline.revision(doubled).unwrap();
+    assert!(output.explain(&timeline.begin()).is_empty());
+
+    timeline.update().update(count, 2);
+
+    let explanation = timeline.explain(doubled, rendered);
+    assert!(explanation.is_stale());
+    assert_eq!(output.explain(&timeline.begin()), vec![explanation]);
+}
@@ -0,0 +1,91 @@
+use std::{cell::Cell, cell::RefCell, collections::HashMap, rc::Rc};
+
+use everafter::{
+    inputs::ExternalStorage,
+    timeline::{EvaluationContext, Timeline},
+};
+
+/// A cache that's owned by the host application, and shared with the timeline.
+#[derive(Debug, Clone, Default)]
+struct Cache {
+    entries: Rc<RefCell<HashMap<String, u32>>>,
+}
+
+impl Cache {
+    fn set(&self, key: &str, value: u32) {
+        self.entries.borrow_mut().insert(key.to_string(), value);
+    }
+}
+
+impl ExternalStorage<u32> for Cache {
+    fn read(&self) -> u32 {
+        self.entries.borrow().values().sum()
+    }
+}
+
+#[test]
+fn external_storage() {
+    let mut timeline = Timeline::new();
+    let cache = Cache::default();
+    cache.set("a", 1);
+
+    let computed = Rc::new(Cell::new(0));
+
+    let mut transaction = timeline.setup();
+    let total = transaction.external(cache.clone());
+    let label = {
+        let computed = computed.clone();
+        transaction.memo(move |ctx: &mut EvaluationContext| {
+            computed.set(computed.get() + 1);
+            format!("total: {}", ctx.value(total))
+        })
+    };
+
+    let mut output = timeline.output(label);
+    let mut transaction = timeline.begin();
+    output.update(&mut transaction);
+    assert_eq!(output.value(), "total: 1");
+
+    // changing the storage without dirtying it isn't observed
+    cache.set("b", 2);
+
+    let mut transaction = timeline.begin();
+    assert!(!output.update(&mut transaction));
+    assert_eq!(computed.get(), 1);
+
+    let revision = timeline.revision(total);
+    timeline.update().dirty(total);
+    assert!(timeline.revision(total) > revision);
+
+    let mut transaction = timeline.begin();
+    assert!(output.update(&mut transaction));
+    assert_eq!(output.value(), "total: 3");
+    assert_eq!(computed.get(), 2);
+}
+
+#[test]
+fn external_closure() {
+    let mut timeline = Timeline::new();
+    let source = Rc::new(Cell::new(10u32));
+
+    let mut transaction = timeline.setup();
+    let value = {
+        let source = source.clone();
+        transaction.external(move || source.get())
+    };
+
+    let mut output = timeline.output(value);
+    let mut transaction = timeline.begin();
+    output.update(&mut transaction);
+    assert_eq!(output.value(), 10);
+
+    // the storage already changed, so rolling back a staged dirty keeps it
+    source.set(20);
+    let revision = timeline.revision(value);
+    timeline.stage().dirty(value);
+    assert_ne!(timeline.revision(value), revision);
+
+    let mut transaction = timeline.begin();
+    assert!(output.update(&mut transaction));
+    assert_eq!(output.value(), 20);
+}
@@ -145,7 +145,7 @@ fn test_function_calling_function() {
     });
 
     func!(print_people(people: Vec<Person>) -> String {
-        format!("{}", itertools::Itertools::join(&mut people.iter().map(|p| &p.name), " and "))
+        itertools::Itertools::join(&mut people.iter().map(|p| &p.name), " and ")
     });
 
     // initialize inputs
@@ -0,0 +1,115 @@
+use everafter::timeline::{DynId, EvaluationContext, GraphEdge, GraphNode, Timeline};
+
+#[test]
+fn dependency_graph() {
+    let mut timeline = Timeline::new();
+
+    let mut transaction = timeline.setup();
+    let first = transaction.cell("Matt".to_string());
+    let last = transaction.cell("Hammer".to_string());
+    let greeting = transaction.constant("Hello".to_string());
+    let full_name = transaction.derived(move |ctx: &mut EvaluationContext| {
+        format!("{} {}", ctx.value(first), ctx.value(last))
+    });
+    let message = transaction.memo(move |ctx: &mut EvaluationContext| {
+        format!("{}, {}", ctx.value(greeting), ctx.value(full_name))
+    });
+
+    let output = timeline.output(message);
+    timeline.register(output);
+
+    let input = |id: DynId| GraphNode::Input(id);
+    let output = GraphNode::Output {
+        index: 0,
+        type_name: "everafter::outputs::primitive::PrimitiveOutput<alloc::string::String>",
+    };
+
+    // nothing was computed yet, so only the output knows what it reads
+    let graph = timeline.dependency_graph();
+    assert_eq!(
+        graph.nodes(),
+        &vec![
+            input(first.into()),
+            input(last.into()),
+            input(greeting.into()),
+            input(full_name.into()),
+            input(message.into()),
+            output,
+        ]
+    );
+    assert_eq!(
+        graph.edges(),
+        &vec![GraphEdge {
+            from: input(message.into()),
+            to: output
+        }]
+    );
+
+    timeline.render();
+
+    // reading a constant is recorded too, after the inputs that can change
+    let graph = timeline.dependency_graph();
+    assert_eq!(
+        graph
+            .dependencies(input(full_name.into()))
+            .collect::<Vec<_>>(),
+        vec![input(first.into()), input(last.into())]
+    );
+    assert_eq!(
+        graph
+            .dependencies(input(message.into()))
+            .collect::<Vec<_>>(),
+        vec![input(full_name.into()), input(greeting.into())]
+    );
+    assert_eq!(graph.edges().len(), 5);
+
+    // deleting an input drops its edges
+    timeline.update().delete(last);
+    let graph = timeline.dependency_graph();
+    assert!(!graph.nodes().contains(&input(last.into())));
+    assert_eq!(
+        graph
+            .dependencies(input(full_name.into()))
+            .collect::<Vec<_>>(),
+        vec![input(first.into())]
+    );
+}
+
+#[test]
+fn exports() {
+    let mut timeline = Timeline::new();
+
+    let mut transaction = timeline.setup();
+    let count = transaction.cell(1u32);
+    let double = transaction.derived(move |ctx: &mut EvaluationContext| ctx.value(count) * 2);
+
+    let mut output = timeline.output(double);
+    output.update(&mut timeline.begin());
+
+    let graph = timeline.dependency_graph();
+
+    assert_eq!(
+        graph.to_dot(),
+        [
+            "digraph dependencies {",
+            "    \"CellId<u32>(0)\" [shape=box];",
+            "    \"DerivedId<u32>(0)\" [shape=ellipse];",
+            "    \"CellId<u32>(0)\" -> \"DerivedId<u32>(0)\";",
+            "}",
+            "",
+        ]
+        .join("\n")
+    );
+
+    assert_eq!(
+        graph.to_json(),
+        concat!(
+            r#"{"nodes":["#,
+            r#"{"id":"CellId<u32>(0)","kind":"CellId","type_name":"u32"},"#,
+            r#"{"id":"DerivedId<u32>(0)","kind":"DerivedId","type_name":"u32"}"#,
+            r#"],"edges":["#,
+            r#"{"from":"CellId<u32>(0)","to":"DerivedId<u32>(0)"}"#,
+            r#"]}"#
+        )
+    );
+}
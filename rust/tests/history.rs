@@ -0,0 +1,137 @@
+use everafter::{
+    timeline::{DynId, EvaluationContext, Timeline},
+    Error,
+};
+
+#[test]
+fn value_at_past_revisions() {
+    let mut timeline = Timeline::new();
+
+    let mut transaction = timeline.setup();
+    let first = transaction.cell_with_history("Matt".to_string(), 10);
+    let last = transaction.cell("Hammer".to_string());
+    let full_name = transaction.memo(move |ctx: &mut EvaluationContext| {
+        format!("{} {}", ctx.value(first), ctx.value(last))
+    });
+
+    let start = timeline.revision(first).unwrap();
+    timeline.update().update(first, "Matthew".to_string());
+    let renamed = timeline.revision(first).unwrap();
+    timeline.update().update(first, "Matty".to_string());
+
+    assert_eq!(timeline.value_at(full_name, start), "Matt Hammer");
+    assert_eq!(timeline.value_at(full_name, renamed), "Matthew Hammer");
+    let current = timeline.revision(first).unwrap();
+    assert_eq!(timeline.value_at(full_name, current), "Matty Hammer");
+
+    // a cell without a history only knows its present value
+    timeline.update().update(last, "Hammersmith".to_string());
+    assert_eq!(
+        timeline.try_value_at(last, start),
+        Err(Error::HistoryUnavailable {
+            id: DynId::from(last),
+            revision: start,
+        })
+    );
+}
+
+#[test]
+fn step_back_and_replay() {
+    let mut timeline = Timeline::new();
+
+    let mut transaction = timeline.setup();
+    let count = transaction.cell_with_history(1u32, 2);
+    let double = transaction.derived(move |ctx: &mut EvaluationContext| ctx.value(count) * 2);
+
+    for value in 2..=4 {
+        timeline.update().update(count, value);
+    }
+
+    // only the last two values before the current one are remembered
+    let updates = timeline.updates();
+    assert_eq!(updates.len(), 3);
+
+    let mut revision = *updates.last().unwrap();
+    let mut seen = vec![timeline.value_at(double, revision)];
+
+    while let Some(previous) = timeline.step_back(revision) {
+        revision = previous;
+        seen.push(timeline.value_at(double, revision));
+    }
+
+    assert_eq!(seen, vec![8, 6, 4]);
+
+    let mut replayed = vec![];
+
+    while let Some(next) = timeline.step_forward(revision) {
+        revision = next;
+        replayed.push(timeline.value_at(double, revision));
+    }
+
+    assert_eq!(replayed, vec![6, 8]);
+}
+
+#[test]
+fn render_at_past_revision() {
+    let mut timeline = Timeline::new();
+
+    let mut transaction = timeline.setup();
+    let count = transaction.cell_with_history(1u32, 10);
+    let double = transaction.derived(move |ctx: &mut EvaluationContext| ctx.value(count) * 2);
+
+    let mut output = timeline.output(double);
+    output.update(&mut timeline.begin());
+    let start = timeline.revision(count).unwrap();
+
+    timeline.update().update(count, 5);
+    output.update(&mut timeline.begin());
+    assert_eq!(output.value(), 10);
+
+    output.update(&mut timeline.begin_at(start));
+    assert_eq!(output.value(), 2);
+
+    // the output is brought back to the present by the next render
+    assert!(output.is_stale(&timeline.begin()));
+    output.update(&mut timeline.begin());
+    assert_eq!(output.value(), 10);
+}
+
+#[test]
+fn rollback_with_history() {
+    let mut timeline = Timeline::new();
+
+    let mut transaction = timeline.setup();
+    let count = transaction.cell_with_history(1u32, 10);
+
+    timeline.update().update(count, 2);
+    let updates = timeline.updates();
+
+    let mut staged = timeline.stage();
+    staged.update(count, 3);
+    staged.modify(count, |count| *count += 1);
+    staged.rollback();
+
+    // the history forgets the writes that were rolled back
+    let current = timeline.revision(count).unwrap();
+    assert_eq!(timeline.value_at(count, current), 2);
+    assert_eq!(timeline.updates(), updates);
+}
+
+#[test]
+fn rollback_past_history_depth() {
+    let mut timeline = Timeline::new();
+
+    let mut transaction = timeline.setup();
+    let count = transaction.cell_with_history(0u32, 1);
+    let start = timeline.revision(count).unwrap();
+
+    let mut staged = timeline.stage();
+    staged.update(count, 1);
+    staged.update(count, 2);
+    staged.modify(count, |count| *count += 1);
+    drop(staged);
+
+    assert_eq!(timeline.revision(count), Some(start));
+    assert_eq!(timeline.value_at(count, start), 0);
+    assert_eq!(timeline.updates(), vec![start]);
+}
@@ -0,0 +1,346 @@
+mod common;
+use common::Test;
+use everafter::{
+    inputs::CopyIntoReactiveList,
+    outputs::{ListChange, ListOutput},
+    timeline::{EvaluationContext, Timeline},
+    Error, GetReactiveKey, Key,
+};
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+struct Todo {
+    id: u32,
+    title: String,
+}
+
+impl Todo {
+    fn new(id: u32, title: impl Into<String>) -> Todo {
+        Todo {
+            id,
+            title: title.into(),
+        }
+    }
+}
+
+impl GetReactiveKey for Todo {
+    fn get_reactive_key(&self) -> Key {
+        Key::number(self.id)
+    }
+}
+
+#[test]
+fn list_input() {
+    let mut test = Test::new();
+
+    let mut todos = test.list(
+        "todos",
+        vec![Todo::new(1, "write code"), Todo::new(2, "write tests")],
+    );
+
+    let todos_handle = todos.handle();
+
+    let titles = test.derived("titles", move |ctx: &mut EvaluationContext| {
+        let todos = ctx.value(todos_handle);
+        todos
+            .iter()
+            .map(|todo| todo.title.clone())
+            .collect::<Vec<_>>()
+            .join(", ")
+    });
+
+    let mut output = titles.output("titles output", &test);
+
+    let mut transaction = test.begin();
+    output.update(&mut transaction);
+    output.expect("write code, write tests", "initial render");
+
+    // push
+    test.edit().push(todos_handle, Todo::new(3, "ship it"));
+    test.assert_changed(&mut todos, "after push");
+
+    let mut transaction = test.begin();
+    assert!(output.update(&mut transaction));
+    output.expect("write code, write tests, ship it", "after push");
+
+    // insert, move and remove in one transaction
+    let mut edit = test.edit();
+    edit.insert(todos_handle, 0, Todo::new(4, "plan"));
+    edit.move_to(todos_handle, &Key::number(3u32), 1);
+    let removed = edit.remove(todos_handle, &Key::number(1u32));
+    assert_eq!(removed, Todo::new(1, "write code"));
+
+    let mut transaction = test.begin();
+    assert!(output.update(&mut transaction));
+    output.expect(
+        "plan, ship it, write tests",
+        "after insert, move and remove",
+    );
+
+    // replace
+    test.edit()
+        .replace(todos_handle, Todo::new(2, "write more tests"));
+
+    let mut transaction = test.begin();
+    assert!(output.update(&mut transaction));
+    output.expect("plan, ship it, write more tests", "after replace");
+
+    test.assert_changed(&mut todos, "after edits");
+
+    // no edits
+    let mut transaction = test.begin();
+    assert!(!output.update(&mut transaction));
+    test.assert_unchanged(&todos, "after render");
+}
+
+#[test]
+fn list_item_revisions() {
+    let mut test = Test::new();
+
+    let todos = test.list(
+        "todos",
+        vec![Todo::new(1, "write code"), Todo::new(2, "write tests")],
+    );
+
+    let todos_handle = todos.handle();
+    let mut output = todos.output("todos output", &test);
+
+    let mut transaction = test.begin();
+    output.update(&mut transaction);
+
+    let before = output.value();
+    let (code, tests) = (&before.items()[0], &before.items()[1]);
+    assert_eq!(code.revision(), tests.revision());
+
+    test.edit()
+        .replace(todos_handle, Todo::new(2, "write more tests"));
+
+    let mut transaction = test.begin();
+    output.update(&mut transaction);
+
+    let after = output.value();
+    assert_eq!(
+        after.get(&Key::number(2u32)).unwrap().title,
+        "write more tests"
+    );
+    assert_eq!(after.items()[0].revision(), code.revision());
+    assert!(after.items()[1].revision() > tests.revision());
+}
+
+/// Apply the changes from the last update of `output` to `rendered`, the way a consumer (such as a
+/// DOM renderer) would, and return how many changes of each kind there were.
+fn apply(rendered: &mut Vec<Todo>, output: &ListOutput<Todo>) -> (usize, usize, usize, usize) {
+    let (mut inserts, mut moves, mut updates, mut removes) = (0, 0, 0, 0);
+
+    let position = |rendered: &Vec<Todo>, key: &Key| {
+        rendered
+            .iter()
+            .position(|todo| &todo.get_reactive_key() == key)
+            .unwrap()
+    };
+
+    let insert = |rendered: &mut Vec<Todo>, todo: Todo, before: &Option<Key>| match before {
+        Some(before) => {
+            let index = position(rendered, before);
+            rendered.insert(index, todo);
+        }
+        None => rendered.push(todo),
+    };
+
+    for change in output.changes() {
+        match change {
+            ListChange::Insert { item, before, .. } => {
+                inserts += 1;
+                insert(rendered, item.clone(), before);
+            }
+            ListChange::Move { key, before } => {
+                moves += 1;
+                let todo = rendered.remove(position(rendered, key));
+                insert(rendered, todo, before);
+            }
+            ListChange::Update { key, item } => {
+                updates += 1;
+                let index = position(rendered, key);
+                rendered[index] = item.clone();
+            }
+            ListChange::Remove { key } => {
+                removes += 1;
+                rendered.remove(position(rendered, key));
+            }
+        }
+    }
+
+    (inserts, moves, updates, removes)
+}
+
+#[test]
+fn list_output() {
+    let mut test = Test::new();
+
+    let todos = test.list(
+        "todos",
+        vec![
+            Todo::new(1, "a"),
+            Todo::new(2, "b"),
+            Todo::new(3, "c"),
+            Todo::new(4, "d"),
+        ],
+    );
+
+    let handle = todos.handle();
+    let mut output = todos.list_output(&test);
+    let mut rendered = vec![];
+
+    let mut transaction = test.begin();
+    assert!(output.update(&mut transaction));
+    assert_eq!(apply(&mut rendered, &output), (4, 0, 0, 0));
+    assert_eq!(rendered, output.value());
+
+    // nothing changed
+    let mut transaction = test.begin();
+    assert!(!output.update(&mut transaction));
+    assert!(output.changes().is_empty());
+
+    // rotating the list is a single move
+    test.edit().move_to(handle, &Key::number(1u32), 3);
+
+    let mut transaction = test.begin();
+    assert!(output.update(&mut transaction));
+    assert_eq!(apply(&mut rendered, &output), (0, 1, 0, 0));
+    assert_eq!(rendered, output.value());
+
+    // swapping the ends of the list is two moves
+    let mut edit = test.edit();
+    edit.move_to(handle, &Key::number(2u32), 3);
+    edit.move_to(handle, &Key::number(1u32), 0);
+
+    let mut transaction = test.begin();
+    assert!(output.update(&mut transaction));
+    assert_eq!(apply(&mut rendered, &output), (0, 2, 0, 0));
+    assert_eq!(rendered, output.value());
+
+    // every kind of change at once
+    let mut edit = test.edit();
+    edit.remove(handle, &Key::number(3u32));
+    edit.insert(handle, 1, Todo::new(5, "e"));
+    edit.replace(handle, Todo::new(4, "D"));
+    edit.move_to(handle, &Key::number(4u32), 0);
+
+    let mut transaction = test.begin();
+    assert!(output.update(&mut transaction));
+    assert_eq!(apply(&mut rendered, &output), (1, 1, 1, 1));
+    assert_eq!(rendered, output.value());
+    assert_eq!(
+        rendered,
+        vec![
+            Todo::new(4, "D"),
+            Todo::new(1, "a"),
+            Todo::new(5, "e"),
+            Todo::new(2, "b"),
+        ]
+    );
+}
+
+#[test]
+#[should_panic(expected = "into a list that already had it")]
+fn duplicate_keys_in_setup() {
+    let mut test = Test::new();
+    test.list(
+        "todos",
+        vec![Todo::new(1, "write code"), Todo::new(1, "write tests")],
+    );
+}
+
+#[test]
+fn derived_list_output() {
+    let mut test = Test::new();
+
+    let todos = test.cell("todos", vec![Todo::new(1, "a"), Todo::new(2, "b")]);
+    let handle = todos.handle();
+
+    // the items of a list that a derived computation copies out of a `Vec` don't have revisions of
+    // their own
+    let list = test.derived("list", move |ctx: &mut EvaluationContext| {
+        ctx.value(handle).copy_into_reactive_list()
+    });
+
+    let mut output = list.list_output(&test);
+    let mut rendered = vec![];
+
+    let mut transaction = test.begin();
+    assert!(output.update(&mut transaction));
+    assert_eq!(apply(&mut rendered, &output), (2, 0, 0, 0));
+
+    // an item that changes under the same key is an update
+    test.edit()
+        .update(handle, vec![Todo::new(1, "a"), Todo::new(2, "B")]);
+
+    let mut transaction = test.begin();
+    assert!(output.update(&mut transaction));
+    assert_eq!(apply(&mut rendered, &output), (0, 0, 1, 0));
+    assert_eq!(rendered, vec![Todo::new(1, "a"), Todo::new(2, "B")]);
+
+    // recomputing the same items isn't a change
+    test.edit()
+        .update(handle, vec![Todo::new(1, "a"), Todo::new(2, "B")]);
+
+    let mut transaction = test.begin();
+    assert!(!output.update(&mut transaction));
+    assert!(output.changes().is_empty());
+}
+
+#[test]
+fn try_list_edits() {
+    let mut timeline = Timeline::new();
+
+    let todos = timeline.setup().list(vec![
+        Todo::new(1, "write code"),
+        Todo::new(2, "write tests"),
+    ]);
+    let revision = timeline.revision(todos).unwrap();
+
+    let mut transaction = timeline.update();
+    assert_eq!(
+        transaction.try_push(todos, Todo::new(1, "again")),
+        Err(Error::DuplicateKey {
+            key: Key::number(1u32)
+        })
+    );
+    assert_eq!(
+        transaction.try_insert(todos, 3, Todo::new(3, "ship it")),
+        Err(Error::OutOfBounds { index: 3, len: 2 })
+    );
+    assert_eq!(
+        transaction.try_replace(todos, Todo::new(3, "ship it")),
+        Err(Error::MissingKey {
+            key: Key::number(3u32)
+        })
+    );
+    assert_eq!(
+        transaction.try_remove(todos, &Key::number(3u32)),
+        Err(Error::MissingKey {
+            key: Key::number(3u32)
+        })
+    );
+    assert_eq!(
+        transaction.try_move_to(todos, &Key::number(1u32), 2),
+        Err(Error::OutOfBounds { index: 2, len: 2 })
+    );
+    transaction.commit();
+
+    // failed edits don't touch the list or advance the timeline
+    assert_eq!(timeline.revision(todos), Some(revision));
+    assert_eq!(timeline.begin().current_revision(), revision);
+
+    let mut transaction = timeline.update();
+    assert_eq!(
+        transaction.try_move_to(todos, &Key::number(1u32), 1),
+        Ok(())
+    );
+    assert_eq!(
+        transaction.try_remove(todos, &Key::number(2u32)),
+        Ok(Todo::new(2, "write tests"))
+    );
+    transaction.commit();
+
+    assert!(timeline.revision(todos).unwrap() > revision);
+}
@@ -0,0 +1,178 @@
+use std::collections::HashMap;
+
+use everafter::{
+    outputs::{MapChange, MapOutput},
+    timeline::{EvaluationContext, Timeline},
+};
+
+/// Apply the changes from the last update of `output` to `attributes`, the way a DOM renderer
+/// would patch an element's attributes.
+fn patch(attributes: &mut HashMap<&'static str, String>, output: &MapOutput<&'static str, String>) {
+    for change in output.changes() {
+        match change {
+            MapChange::Insert { key, value } | MapChange::Update { key, value } => {
+                attributes.insert(key, value.clone());
+            }
+            MapChange::Remove { key } => {
+                attributes.remove(key);
+            }
+        }
+    }
+}
+
+#[test]
+fn map_output() {
+    let mut timeline = Timeline::new();
+
+    // initialize inputs
+    let mut transaction = timeline.setup();
+    let id = transaction.cell("submit".to_string());
+    let kind = transaction.cell("primary".to_string());
+    let class = transaction
+        .derived(move |ctx: &mut EvaluationContext| format!("button button-{}", ctx.value(kind)));
+    let title = transaction.constant("Submit the form".to_string());
+
+    // initialize outputs
+    let mut attributes = MapOutput::new();
+    attributes.insert("id", id);
+    attributes.insert("class", class);
+
+    let mut element = HashMap::new();
+
+    // archive
+    let mut transaction = timeline.begin();
+    assert!(attributes.update(&mut transaction));
+    assert_eq!(attributes.changes().len(), 2);
+    patch(&mut element, &attributes);
+
+    assert_eq!(element["id"], "submit");
+    assert_eq!(element["class"], "button button-primary");
+
+    // edit
+    timeline.update().update(kind, "secondary".to_string());
+
+    // archive
+    let mut transaction = timeline.begin();
+    assert!(attributes.update(&mut transaction));
+    assert_eq!(
+        attributes.changes(),
+        &vec![MapChange::Update {
+            key: "class",
+            value: "button button-secondary".to_string()
+        }],
+        "only the changed attribute is reported"
+    );
+    patch(&mut element, &attributes);
+
+    // archive without edits
+    let mut transaction = timeline.begin();
+    assert!(!attributes.update(&mut transaction));
+    assert!(attributes.changes().is_empty());
+
+    // add and remove attributes
+    attributes.remove(&"id");
+    attributes.insert("title", title);
+
+    let mut transaction = timeline.begin();
+    assert!(attributes.is_stale(&transaction));
+    assert!(attributes.update(&mut transaction));
+    assert_eq!(
+        attributes.changes(),
+        &vec![
+            MapChange::Remove { key: "id" },
+            MapChange::Insert {
+                key: "title",
+                value: "Submit the form".to_string()
+            },
+        ]
+    );
+    patch(&mut element, &attributes);
+
+    let expected: HashMap<_, _> = attributes.value().into_iter().collect();
+    assert_eq!(element, expected);
+    assert_eq!(element.get("id"), None);
+    assert_eq!(element["title"], "Submit the form");
+}
+
+#[test]
+fn reinsert_rendered_entry() {
+    let mut timeline = Timeline::new();
+
+    let mut transaction = timeline.setup();
+    let first = transaction.cell("first".to_string());
+    let second = transaction.cell("second".to_string());
+
+    let mut attributes = MapOutput::new();
+    attributes.insert("k", first);
+    attributes.update(&mut timeline.begin());
+
+    // until the next update, the entry keeps the value that was rendered
+    attributes.insert("k", second);
+    assert_eq!(attributes.get(&"k"), Some("first".to_string()));
+    assert_eq!(attributes.value()["k"], "first");
+
+    assert!(attributes.update(&mut timeline.begin()));
+    assert_eq!(
+        attributes.changes(),
+        &vec![MapChange::Update {
+            key: "k",
+            value: "second".to_string()
+        }]
+    );
+
+    // the same goes for an entry that was removed and inserted again before an update
+    attributes.remove(&"k");
+    attributes.insert("k", first);
+    assert_eq!(attributes.get(&"k"), Some("second".to_string()));
+
+    assert!(attributes.update(&mut timeline.begin()));
+    assert_eq!(
+        attributes.changes(),
+        &vec![MapChange::Update {
+            key: "k",
+            value: "first".to_string()
+        }]
+    );
+}
+
+#[test]
+fn registered_map_output() {
+    let mut timeline = Timeline::new();
+
+    let mut transaction = timeline.setup();
+    let id = transaction.cell("submit".to_string());
+    let title = transaction.cell("Submit the form".to_string());
+
+    let mut attributes = MapOutput::new();
+    attributes.insert("id", id);
+    let attributes = timeline.register(attributes);
+
+    let mut element = HashMap::new();
+
+    assert!(timeline.render());
+    patch(&mut element, timeline.program().get(attributes));
+    assert_eq!(element.len(), 1);
+
+    // entries can still be inserted and removed once the map is registered
+    let registered = timeline.program_mut().get_mut(attributes);
+    registered.insert("title", title);
+    registered.remove(&"id");
+
+    assert!(timeline.is_stale());
+    assert!(timeline.render());
+    assert_eq!(
+        timeline.program().get(attributes).changes(),
+        &vec![
+            MapChange::Remove { key: "id" },
+            MapChange::Insert {
+                key: "title",
+                value: "Submit the form".to_string()
+            }
+        ]
+    );
+
+    patch(&mut element, timeline.program().get(attributes));
+    assert_eq!(element.len(), 1);
+    assert_eq!(element["title"], "Submit the form");
+    assert!(!timeline.render());
+}
@@ -0,0 +1,128 @@
+use std::{cell::Cell, rc::Rc};
+
+use everafter::timeline::{EvaluationContext, Timeline};
+
+/// A value that counts how many times it was cloned.
+#[derive(Debug)]
+struct Big {
+    items: Vec<u32>,
+    clones: Rc<Cell<usize>>,
+}
+
+impl Clone for Big {
+    fn clone(&self) -> Big {
+        self.clones.set(self.clones.get() + 1);
+
+        Big {
+            items: self.items.clone(),
+            clones: self.clones.clone(),
+        }
+    }
+}
+
+#[test]
+fn modify_and_borrow() {
+    let mut timeline = Timeline::new();
+    let clones = Rc::new(Cell::new(0));
+
+    let mut transaction = timeline.setup();
+    let big = transaction.cell(Big {
+        items: (1..=100).collect(),
+        clones: clones.clone(),
+    });
+    let sum = transaction.derived(move |ctx: &mut EvaluationContext| {
+        ctx.with_value(big, |big: &Big| big.items.iter().sum::<u32>())
+    });
+
+    let mut output = timeline.output(sum);
+    let mut transaction = timeline.begin();
+    output.update(&mut transaction);
+    assert_eq!(output.value(), 5050);
+
+    let revision = timeline.revision(big);
+
+    let mut transaction = timeline.update();
+    let len = transaction.modify(big, |big| {
+        big.items.push(1000);
+        big.items.len()
+    });
+    assert_eq!(len, 101);
+    assert!(timeline.revision(big) > revision);
+
+    let mut transaction = timeline.begin();
+    assert!(output.update(&mut transaction));
+    assert_eq!(output.value(), 6050);
+
+    assert_eq!(clones.get(), 0);
+}
+
+#[test]
+fn with_value_derived() {
+    let mut timeline = Timeline::new();
+
+    let mut transaction = timeline.setup();
+    let name = transaction.cell("Matt".to_string());
+    let greeting = transaction
+        .derived(move |ctx: &mut EvaluationContext| format!("Hello {}", ctx.value(name)));
+    let length = transaction.derived(move |ctx: &mut EvaluationContext| {
+        ctx.with_value(greeting, |greeting: &String| greeting.len())
+    });
+
+    let mut output = timeline.output(length);
+    let mut transaction = timeline.begin();
+    output.update(&mut transaction);
+    assert_eq!(output.value(), 10);
+
+    timeline.update().modify(name, |name| name.push_str("hew"));
+
+    let mut transaction = timeline.begin();
+    assert!(output.update(&mut transaction));
+    assert_eq!(output.value(), 13);
+}
+
+/// A handle that can be neither cloned nor debugged.
+struct Handle {
+    name: String,
+}
+
+#[test]
+fn non_clone_cells() {
+    let mut timeline = Timeline::new();
+
+    let mut transaction = timeline.setup();
+    let handle = transaction.cell(Handle {
+        name: "Matt".to_string(),
+    });
+    let name = transaction.derived(move |ctx: &mut EvaluationContext| {
+        ctx.with_value(handle, |handle: &Handle| handle.name.clone())
+    });
+
+    let mut output = timeline.output(name);
+    let mut transaction = timeline.begin();
+    output.update(&mut transaction);
+    assert_eq!(output.value(), "Matt");
+
+    // a staged update can be rolled back without cloning the handle
+    let mut transaction = timeline.stage();
+    transaction.update(
+        handle,
+        Handle {
+            name: "Yehuda".to_string(),
+        },
+    );
+    transaction.rollback();
+
+    let mut transaction = timeline.begin();
+    assert!(!output.update(&mut transaction));
+
+    timeline.update().update(
+        handle,
+        Handle {
+            name: "Godfrey".to_string(),
+        },
+    );
+
+    let mut transaction = timeline.begin();
+    assert!(output.update(&mut transaction));
+    assert_eq!(output.value(), "Godfrey");
+}
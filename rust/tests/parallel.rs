@@ -0,0 +1,142 @@
+#![cfg(feature = "rayon")]
+
+use everafter::{
+    outputs::{PrimitiveOutput, TreeChild, TreeOutput},
+    timeline::{CellId, EvaluationContext, Shared, Timeline, TypedInputIdWithKind},
+};
+
+type Cells = Vec<TypedInputIdWithKind<u64, CellId<u64>>>;
+
+/// A row of cells, and a memo for every cell that sums the cell and its neighbours.
+fn setup(timeline: &mut Timeline<Shared>) -> (Cells, Vec<PrimitiveOutput<u64>>) {
+    let mut transaction = timeline.setup();
+    let cells: Cells = (0..64).map(|i| transaction.cell(i)).collect();
+
+    let sums: Vec<_> = (0..cells.len())
+        .map(|i| {
+            let neighbours = cells[i.saturating_sub(1)..(i + 2).min(cells.len())].to_vec();
+            transaction.memo(move |ctx: &mut EvaluationContext| {
+                neighbours.iter().map(|cell| ctx.value(*cell)).sum::<u64>()
+            })
+        })
+        .collect();
+
+    let outputs = sums.into_iter().map(|sum| timeline.output(sum)).collect();
+    (cells, outputs)
+}
+
+fn values(outputs: &[PrimitiveOutput<u64>]) -> Vec<u64> {
+    outputs.iter().map(PrimitiveOutput::value).collect()
+}
+
+/// Render `sequential` one output at a time and `parallel` on the thread pool, and check that both
+/// renders agree.
+fn render(
+    sequential: &Timeline<Shared>,
+    sequential_outputs: &mut [PrimitiveOutput<u64>],
+    parallel: &Timeline<Shared>,
+    parallel_outputs: &mut [PrimitiveOutput<u64>],
+) -> bool {
+    let mut transaction = sequential.begin();
+    let mut sequential_changed = false;
+    for output in sequential_outputs.iter_mut() {
+        sequential_changed |= output.update(&mut transaction);
+    }
+
+    let parallel_changed = parallel.par_begin().par_update(parallel_outputs);
+
+    assert_eq!(parallel_changed, sequential_changed);
+    assert_eq!(values(parallel_outputs), values(sequential_outputs));
+    parallel_changed
+}
+
+#[test]
+fn parallel_outputs() {
+    let mut sequential = Timeline::shared();
+    let (sequential_cells, mut sequential_outputs) = setup(&mut sequential);
+
+    let mut parallel = Timeline::shared();
+    let (parallel_cells, mut parallel_outputs) = setup(&mut parallel);
+
+    assert!(render(
+        &sequential,
+        &mut sequential_outputs,
+        &parallel,
+        &mut parallel_outputs
+    ));
+
+    for (timeline, cells) in [
+        (&mut sequential, &sequential_cells),
+        (&mut parallel, &parallel_cells),
+    ] {
+        let mut transaction = timeline.update();
+        transaction.update(cells[10], 100);
+        transaction.update(cells[40], 400);
+    }
+
+    assert!(render(
+        &sequential,
+        &mut sequential_outputs,
+        &parallel,
+        &mut parallel_outputs
+    ));
+
+    // nothing changed since the last render
+    assert!(!render(
+        &sequential,
+        &mut sequential_outputs,
+        &parallel,
+        &mut parallel_outputs
+    ));
+}
+
+#[test]
+fn parallel_tree() {
+    let mut timeline = Timeline::shared();
+    let (cells, outputs) = setup(&mut timeline);
+
+    // a node for every eight outputs
+    let mut outputs = outputs.into_iter().peekable();
+    let mut nodes = vec![];
+    while outputs.peek().is_some() {
+        let leaves = outputs.by_ref().take(8).map(TreeChild::leaf).collect();
+        nodes.push(TreeChild::node(TreeOutput::shared(leaves)));
+    }
+    let mut tree = TreeOutput::shared(nodes);
+
+    let sum = |tree: &TreeOutput<Shared>, index: usize| {
+        tree.leaf::<PrimitiveOutput<u64>>(&[index / 8, index % 8])
+            .unwrap()
+            .value()
+    };
+
+    assert!(tree.par_update(&timeline.par_begin()));
+    assert_eq!(sum(&tree, 0), 1);
+    assert_eq!(sum(&tree, 20), 19 + 20 + 21);
+
+    timeline.update().update(cells[20], 0);
+
+    assert!(tree.par_update(&timeline.par_begin()));
+    assert_eq!(sum(&tree, 20), 19 + 21);
+    assert_eq!(sum(&tree, 21), 22 + 21);
+    assert!(!tree.par_update(&timeline.par_begin()));
+}
+
+#[test]
+fn parallel_program() {
+    let mut timeline = Timeline::shared();
+    let (cells, outputs) = setup(&mut timeline);
+    let ids: Vec<_> = outputs
+        .into_iter()
+        .map(|output| timeline.register(output))
+        .collect();
+
+    assert!(timeline.par_render());
+    assert_eq!(timeline.program().get(ids[63]).value(), 62 + 63);
+
+    timeline.update().update(cells[63], 0);
+
+    assert!(timeline.par_render());
+    assert_eq!(timeline.program().get(ids[63]).value(), 62);
+    assert!(!timeline.par_render());
+}
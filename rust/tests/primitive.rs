@@ -1,6 +1,7 @@
 mod common;
 use common::Test;
 use everafter::{timeline::EvaluationContext, GetReactiveKey, Key, TypedInputId};
+use std::{cell::Cell, rc::Rc};
 use uuid::Uuid;
 
 #[test]
@@ -79,6 +80,158 @@ fn primitive_derived() {
     output2.expect(15, "after update");
 }
 
+#[test]
+fn primitive_validation() {
+    let mut test = Test::new();
+
+    let mut input1 = test.cell("input1", 1);
+    let input2 = test.cell("input2", 2);
+
+    let i1 = input1.handle();
+    let i2 = input2.handle();
+
+    let computed = Rc::new(Cell::new(0));
+    let counter = computed.clone();
+
+    let derived = test.derived("derived", move |ctx: &mut EvaluationContext| -> i32 {
+        counter.set(counter.get() + 1);
+        ctx.value(i1) + ctx.value(i2)
+    });
+
+    let mut output = derived.output("output", &test);
+
+    // render
+    let mut transaction = test.begin();
+    assert!(output.is_stale(&transaction), "never rendered");
+    assert!(output.update(&mut transaction), "initial render");
+    output.expect(3, "initial value");
+    assert_eq!(computed.get(), 1);
+
+    // render again without any edits
+    let mut transaction = test.begin();
+    assert!(!output.is_stale(&transaction), "nothing changed");
+    assert!(!output.update(&mut transaction), "nothing changed");
+    output.expect(3, "after no-op render");
+    assert_eq!(computed.get(), 1, "the derived value wasn't recomputed");
+
+    // edit
+    input1.update(&mut test, 5);
+
+    let mut transaction = test.begin();
+    assert!(output.is_stale(&transaction), "after update");
+    assert!(output.update(&mut transaction), "after update");
+    output.expect(7, "after update");
+    assert_eq!(computed.get(), 2, "the derived value was recomputed");
+}
+
+#[test]
+fn primitive_memo() {
+    let mut test = Test::new();
+
+    let mut first = test.cell("first", "Matt".to_string());
+    let last = test.cell("last", "Hammer".to_string());
+    let mut title = test.cell("title", "Dr.".to_string());
+
+    let first_handle = first.handle();
+    let last_handle = last.handle();
+    let title_handle = title.handle();
+
+    let computed = Rc::new(Cell::new(0));
+    let counter = computed.clone();
+
+    let full_name = test.memo("full name", move |ctx: &mut EvaluationContext| {
+        counter.set(counter.get() + 1);
+        format!("{} {}", ctx.value(first_handle), ctx.value(last_handle))
+    });
+
+    let full_name_handle = full_name.handle();
+
+    let greeting = test.derived("greeting", move |ctx: &mut EvaluationContext| {
+        format!(
+            "{} {}",
+            ctx.value(title_handle),
+            ctx.value(full_name_handle)
+        )
+    });
+
+    let mut output = greeting.output("greeting output", &test);
+
+    let mut transaction = test.begin();
+    output.update(&mut transaction);
+    output.expect("Dr. Matt Hammer", "initial render");
+    assert_eq!(computed.get(), 1);
+
+    // edit an input that the memo doesn't depend on
+    title.update(&mut test, "Prof.".to_string());
+
+    let mut transaction = test.begin();
+    output.update(&mut transaction);
+    output.expect("Prof. Matt Hammer", "after title update");
+    assert_eq!(computed.get(), 1, "the memo wasn't recomputed");
+
+    // edit an input that the memo depends on
+    first.update(&mut test, "Matthew".to_string());
+
+    let mut transaction = test.begin();
+    output.update(&mut transaction);
+    output.expect("Prof. Matthew Hammer", "after first update");
+    assert_eq!(computed.get(), 2, "the memo was recomputed");
+}
+
+#[test]
+fn primitive_constant() {
+    let mut test = Test::new();
+
+    let greeting = test.constant("greeting", "Hello".to_string());
+    let mut name = test.cell("name", "Matt".to_string());
+
+    let greeting_handle = greeting.handle();
+    let name_handle = name.handle();
+
+    let loud = test.derived("loud", move |ctx: &mut EvaluationContext| {
+        ctx.value(greeting_handle).to_uppercase()
+    });
+
+    let loud_handle = loud.handle();
+
+    let message = test.derived("message", move |ctx: &mut EvaluationContext| {
+        format!("{} {}", ctx.value(loud_handle), ctx.value(name_handle))
+    });
+
+    let mut greeting_output = greeting.output("greeting output", &test);
+    let mut loud_output = loud.output("loud output", &test);
+    let mut message_output = message.output("message output", &test);
+
+    let mut transaction = test.begin();
+    greeting_output.update(&mut transaction);
+    loud_output.update(&mut transaction);
+    message_output.update(&mut transaction);
+
+    greeting_output.expect("Hello", "initial render");
+    loud_output.expect("HELLO", "initial render");
+    message_output.expect("HELLO Matt", "initial render");
+
+    assert!(greeting_output.is_constant(), "a constant is constant");
+    assert!(
+        loud_output.is_constant(),
+        "a derived value whose dependencies are all constant is constant"
+    );
+    assert!(
+        !message_output.is_constant(),
+        "a derived value that depends on a cell isn't constant"
+    );
+
+    // edit
+    name.update(&mut test, "Matthew".to_string());
+
+    let mut transaction = test.begin();
+    assert!(!greeting_output.update(&mut transaction));
+    assert!(!loud_output.update(&mut transaction));
+    assert!(message_output.update(&mut transaction));
+
+    message_output.expect("HELLO Matthew", "after update");
+}
+
 #[derive(Debug, Clone, Eq, PartialEq)]
 enum Location {
     UnitedStates,
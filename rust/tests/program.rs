@@ -0,0 +1,126 @@
+use std::{cell::Cell, rc::Rc};
+
+use everafter::{
+    outputs::{ListChange, Output, TreeChild, TreeOutput},
+    timeline::{EvaluationContext, Timeline},
+    GetReactiveKey, Key,
+};
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+struct Tag {
+    name: &'static str,
+}
+
+impl GetReactiveKey for Tag {
+    fn get_reactive_key(&self) -> Key {
+        Key::string(self.name)
+    }
+}
+
+#[test]
+fn program_render() {
+    let mut timeline = Timeline::new();
+
+    // initialize inputs
+    let mut transaction = timeline.setup();
+    let first = transaction.cell("Matt".to_string());
+    let last = transaction.cell("Hammer".to_string());
+    let tags = transaction.list(vec![
+        Tag { name: "rust" },
+        Tag {
+            name: "incremental",
+        },
+    ]);
+
+    let computed = Rc::new(Cell::new(0));
+    let counter = computed.clone();
+
+    let full_name = transaction.derived(move |ctx: &mut EvaluationContext| {
+        counter.set(counter.get() + 1);
+        format!("{} {}", ctx.value(first), ctx.value(last))
+    });
+
+    // initialize outputs
+    let first_output = timeline.output(first);
+    let name = timeline.register(TreeOutput::new(vec![TreeChild::leaf(
+        timeline.output(full_name),
+    )]));
+    let first = timeline.register(first_output);
+    let tag_list = timeline.list_output(tags);
+    let tag_list = timeline.register(tag_list);
+
+    // render
+    assert!(timeline.is_stale());
+    assert!(timeline.render());
+
+    assert_eq!(timeline.program().get(first).value(), "Matt");
+    assert_eq!(timeline.program().get(tag_list).changes().len(), 2);
+    assert_eq!(computed.get(), 1);
+
+    // render without edits
+    assert!(!timeline.is_stale());
+    assert!(!timeline.render());
+    assert_eq!(computed.get(), 1);
+
+    // edit
+    timeline.update().push(tags, Tag { name: "reactivity" });
+
+    // render
+    assert!(timeline.render());
+    assert_eq!(computed.get(), 1, "only the stale output was updated");
+    assert_eq!(
+        timeline.program().get(tag_list).changes(),
+        &vec![ListChange::Insert {
+            key: Key::string("reactivity"),
+            item: Tag { name: "reactivity" },
+            before: None,
+        }]
+    );
+
+    // unregistered outputs aren't rendered
+    let mut name = timeline.unregister(name);
+    timeline.update().update(last, "Hammer-Acar".to_string());
+
+    assert!(!timeline.render());
+    assert_eq!(computed.get(), 1);
+
+    let mut transaction = timeline.begin();
+    assert!(name.update(&mut transaction));
+    assert_eq!(computed.get(), 2);
+}
+
+#[test]
+fn program_get_mut() {
+    let mut timeline = Timeline::new();
+
+    let mut transaction = timeline.setup();
+    let name = transaction.cell("Matt".to_string());
+
+    let output = timeline.output(name);
+    let output = timeline.register(output);
+
+    assert!(timeline.render());
+    assert_eq!(timeline.program_mut().get_mut(output).value(), "Matt");
+
+    timeline.update().update(name, "Yehuda".to_string());
+    assert!(timeline.render());
+    assert_eq!(timeline.program_mut().get_mut(output).value(), "Yehuda");
+}
+
+#[test]
+#[should_panic(expected = "OutputId was used with the wrong program")]
+fn output_id_from_another_program() {
+    let mut first = Timeline::new();
+    let mut transaction = first.setup();
+    let name = transaction.cell("Matt".to_string());
+    let output = first.output(name);
+    let output = first.register(output);
+
+    let mut second = Timeline::new();
+    let mut transaction = second.setup();
+    let name = transaction.cell("Yehuda".to_string());
+    let other = second.output(name);
+    second.register(other);
+
+    second.program().get(output);
+}
@@ -0,0 +1,83 @@
+use everafter::{
+    outputs::SetChange,
+    timeline::{EvaluationContext, Timeline},
+};
+
+#[test]
+fn set_output() {
+    let mut timeline = Timeline::new();
+
+    // initialize inputs
+    let mut transaction = timeline.setup();
+    let active = transaction.cell(false);
+    let kind = transaction.cell("primary");
+    let classes = transaction.derived(move |ctx: &mut EvaluationContext| {
+        let mut classes = vec!["button".to_string()];
+        classes.push(format!("button-{}", ctx.value(kind)));
+
+        if ctx.value(active) {
+            classes.push("active".to_string());
+        }
+
+        classes
+    });
+
+    // initialize outputs
+    let mut class_list = timeline.set_output(classes);
+
+    // archive
+    let mut transaction = timeline.begin();
+    assert!(class_list.update(&mut transaction));
+    assert_eq!(
+        class_list.changes(),
+        &vec![
+            SetChange::Insert {
+                member: "button".to_string()
+            },
+            SetChange::Insert {
+                member: "button-primary".to_string()
+            },
+        ]
+    );
+
+    // edit
+    let mut transaction = timeline.update();
+    transaction.update(active, true);
+    transaction.update(kind, "secondary");
+
+    // archive
+    let mut transaction = timeline.begin();
+    assert!(class_list.update(&mut transaction));
+    assert_eq!(
+        class_list.changes(),
+        &vec![
+            SetChange::Remove {
+                member: "button-primary".to_string()
+            },
+            SetChange::Insert {
+                member: "button-secondary".to_string()
+            },
+            SetChange::Insert {
+                member: "active".to_string()
+            },
+        ]
+    );
+
+    assert!(class_list.contains(&"button".to_string()));
+    assert!(class_list.contains(&"active".to_string()));
+    assert!(!class_list.contains(&"button-primary".to_string()));
+
+    // archive without edits
+    let mut transaction = timeline.begin();
+    assert!(!class_list.is_stale(&transaction));
+    assert!(!class_list.update(&mut transaction));
+    assert!(class_list.changes().is_empty());
+
+    // an edit that recomputes the same members doesn't report any changes
+    timeline.update().update(kind, "secondary");
+
+    let mut transaction = timeline.begin();
+    assert!(class_list.is_stale(&transaction));
+    assert!(!class_list.update(&mut transaction));
+    assert!(class_list.changes().is_empty());
+}
@@ -0,0 +1,135 @@
+use std::{
+    rc::Rc,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    },
+    thread,
+};
+
+use everafter::timeline::{EvaluationContext, Shared, SharedTimeline, Timeline};
+
+fn assert_send_sync<T: Send + Sync>() {}
+
+#[test]
+fn timelines_are_send_and_sync() {
+    assert_send_sync::<Timeline<Shared>>();
+    assert_send_sync::<SharedTimeline>();
+}
+
+#[test]
+fn local_timelines_hold_anything() {
+    let mut timeline = Timeline::new();
+
+    let mut transaction = timeline.setup();
+    let name = transaction.cell(Rc::new("Matt".to_string()));
+    let suffix = Rc::new(" Hammer".to_string());
+    let full_name = transaction
+        .derived(move |ctx: &mut EvaluationContext| format!("{}{}", ctx.value(name), suffix));
+
+    let mut output = timeline.output(full_name);
+    output.update(&mut timeline.begin());
+    assert_eq!(output.value(), "Matt Hammer");
+}
+
+#[test]
+fn concurrent_renders() {
+    let timeline = SharedTimeline::new();
+    let computed = Arc::new(AtomicUsize::new(0));
+
+    let (first, full_name) = timeline.setup(|transaction| {
+        let first = transaction.cell("Matt".to_string());
+        let last = transaction.cell("Hammer".to_string());
+        let computed = computed.clone();
+        let full_name = transaction.memo(move |ctx: &mut EvaluationContext| {
+            computed.fetch_add(1, Ordering::SeqCst);
+            format!("{} {}", ctx.value(first), ctx.value(last))
+        });
+        (first, full_name)
+    });
+
+    let render = |timeline: &SharedTimeline| {
+        let handles: Vec<_> = (0..4)
+            .map(|_| {
+                let timeline = timeline.clone();
+                thread::spawn(move || {
+                    let mut output = timeline.read(|timeline| timeline.output(full_name));
+                    timeline.render(|transaction| output.update(transaction));
+                    output.value()
+                })
+            })
+            .collect();
+
+        handles
+            .into_iter()
+            .map(|handle| handle.join().unwrap())
+            .collect::<Vec<_>>()
+    };
+
+    assert_eq!(render(&timeline), vec!["Matt Hammer"; 4]);
+
+    // every thread may have computed the memo, but once it's memoized, nobody recomputes it
+    let after_first_render = computed.load(Ordering::SeqCst);
+    assert!((1..=4).contains(&after_first_render));
+
+    assert_eq!(render(&timeline), vec!["Matt Hammer"; 4]);
+    assert_eq!(computed.load(Ordering::SeqCst), after_first_render);
+
+    timeline.update(|transaction| transaction.update(first, "Matthew".to_string()));
+    assert_eq!(render(&timeline), vec!["Matthew Hammer"; 4]);
+}
+
+#[test]
+fn renders_never_observe_part_of_an_update() {
+    let timeline = SharedTimeline::new();
+
+    let (first, last, same) = timeline.setup(|transaction| {
+        let first = transaction.cell(0u32);
+        let last = transaction.cell(0u32);
+        let same = transaction
+            .derived(move |ctx: &mut EvaluationContext| ctx.value(first) == ctx.value(last));
+        (first, last, same)
+    });
+
+    let writer = {
+        let timeline = timeline.clone();
+        thread::spawn(move || {
+            for i in 1..=100 {
+                timeline.update(|transaction| {
+                    transaction.update(first, i);
+                    transaction.update(last, i);
+                });
+            }
+        })
+    };
+
+    let readers: Vec<_> = (0..4)
+        .map(|_| {
+            let timeline = timeline.clone();
+            thread::spawn(move || {
+                let mut output = timeline.read(|timeline| timeline.output(same));
+                let mut last_revision = None;
+
+                for _ in 0..100 {
+                    let revision = timeline.render(|transaction| {
+                        output.update(transaction);
+                        transaction.current_revision()
+                    });
+
+                    assert!(output.value(), "a render observed half of an update");
+                    assert!(Some(revision) >= last_revision);
+                    last_revision = Some(revision);
+                }
+            })
+        })
+        .collect();
+
+    writer.join().unwrap();
+    for reader in readers {
+        reader.join().unwrap();
+    }
+
+    let mut output = timeline.read(|timeline| timeline.output(first));
+    timeline.render(|transaction| output.update(transaction));
+    assert_eq!(output.value(), 100);
+}
@@ -0,0 +1,118 @@
+#![cfg(feature = "serde")]
+
+use everafter::{
+    timeline::{CellId, DerivedId, EvaluationContext, Snapshot, Timeline, TypedInputIdWithKind},
+    Error,
+};
+
+struct Session {
+    timeline: Timeline,
+    first: TypedInputIdWithKind<String, CellId<String>>,
+    visits: TypedInputIdWithKind<u32, CellId<u32>>,
+    summary: TypedInputIdWithKind<String, DerivedId<String>>,
+}
+
+impl Session {
+    fn new() -> Session {
+        let mut timeline = Timeline::new();
+
+        let mut transaction = timeline.setup();
+        let first = transaction.cell("Matt".to_string());
+        let last = transaction.cell("Hammer".to_string());
+        let visits = transaction.cell(0u32);
+        transaction.persist("first", first);
+        transaction.persist("last", last);
+        transaction.persist("visits", visits);
+
+        let summary = transaction.memo(move |ctx: &mut EvaluationContext| {
+            format!(
+                "{} {} ({})",
+                ctx.value(first),
+                ctx.value(last),
+                ctx.value(visits)
+            )
+        });
+
+        Session {
+            timeline,
+            first,
+            visits,
+            summary,
+        }
+    }
+
+    fn render(&self) -> String {
+        let mut output = self.timeline.output(self.summary);
+        output.update(&mut self.timeline.begin());
+        output.value()
+    }
+}
+
+#[test]
+fn snapshot_and_restore() {
+    let mut session = Session::new();
+
+    let mut transaction = session.timeline.update();
+    transaction.update(session.first, "Matthew".to_string());
+    transaction.update(session.visits, 3);
+    transaction.commit();
+
+    let json = serde_json::to_string(&session.timeline.snapshot()).unwrap();
+    assert_eq!(json, r#"{"first":"Matthew","last":"Hammer","visits":3}"#);
+
+    let mut resumed = Session::new();
+    assert_eq!(resumed.render(), "Matt Hammer (0)");
+
+    let snapshot: Snapshot = serde_json::from_str(&json).unwrap();
+    resumed.timeline.restore(&snapshot);
+    assert_eq!(resumed.render(), "Matthew Hammer (3)");
+}
+
+#[test]
+fn restore_is_all_or_nothing() {
+    let mut session = Session::new();
+
+    let snapshot: Snapshot =
+        serde_json::from_str(r#"{"first":"Matthew","visits":"many"}"#).unwrap();
+
+    match session.timeline.try_restore(&snapshot) {
+        Err(Error::Snapshot { name, .. }) => assert_eq!(name, "visits"),
+        other => panic!("expected a snapshot error, got {:?}", other),
+    }
+
+    assert_eq!(session.render(), "Matt Hammer (0)");
+
+    // unknown names are ignored, and cells that aren't in the snapshot keep their value
+    let snapshot: Snapshot = serde_json::from_str(r#"{"first":"Matthew","unknown":true}"#).unwrap();
+    session.timeline.restore(&snapshot);
+    assert_eq!(session.render(), "Matthew Hammer (0)");
+}
+
+#[test]
+fn deleted_cells_are_left_out() {
+    let mut session = Session::new();
+    session.timeline.update().delete(session.visits);
+
+    let snapshot = session.timeline.snapshot();
+    assert_eq!(
+        snapshot.cells().keys().collect::<Vec<_>>(),
+        vec!["first", "last"]
+    );
+}
+
+#[test]
+fn names_are_unique() {
+    let mut timeline = Timeline::new();
+
+    let mut transaction = timeline.setup();
+    let first = transaction.cell("Matt".to_string());
+    let last = transaction.cell("Hammer".to_string());
+    transaction.persist("name", first);
+
+    assert_eq!(
+        transaction.try_persist("name", last),
+        Err(Error::NameTaken {
+            name: "name".to_string()
+        })
+    );
+}
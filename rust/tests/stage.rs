@@ -0,0 +1,133 @@
+use everafter::{
+    timeline::{EvaluationContext, Timeline},
+    GetReactiveKey, Key,
+};
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+struct Field {
+    name: &'static str,
+    value: String,
+}
+
+impl GetReactiveKey for Field {
+    fn get_reactive_key(&self) -> Key {
+        Key::string(self.name)
+    }
+}
+
+#[test]
+fn rollback_restores_values_and_revisions() {
+    let mut timeline = Timeline::new();
+
+    let mut transaction = timeline.setup();
+    let first = transaction.cell("Matt".to_string());
+    let last = transaction.cell("Hammer".to_string());
+    let full_name = transaction.derived(move |ctx: &mut EvaluationContext| {
+        format!("{} {}", ctx.value(first), ctx.value(last))
+    });
+
+    let mut output = timeline.output(full_name);
+    let mut transaction = timeline.begin();
+    output.update(&mut transaction);
+
+    let first_revision = timeline.revision(first);
+    let last_revision = timeline.revision(last);
+
+    let mut transaction = timeline.stage();
+    transaction.update(first, "Yehuda".to_string());
+    transaction.update(last, "Katz".to_string());
+    transaction.update(first, "Godfrey".to_string());
+    transaction.rollback();
+
+    assert_eq!(timeline.revision(first), first_revision);
+    assert_eq!(timeline.revision(last), last_revision);
+
+    let mut transaction = timeline.begin();
+    assert!(!output.update(&mut transaction));
+    assert_eq!(output.value(), "Matt Hammer");
+
+    // dropping a staged transaction without committing it also rolls it back
+    {
+        let mut transaction = timeline.stage();
+        transaction.update(first, "Yehuda".to_string());
+    }
+
+    let mut transaction = timeline.begin();
+    assert!(!output.update(&mut transaction));
+
+    let mut transaction = timeline.stage();
+    transaction.update(first, "Yehuda".to_string());
+    transaction.update(last, "Katz".to_string());
+    transaction.commit();
+
+    let mut transaction = timeline.begin();
+    assert!(output.update(&mut transaction));
+    assert_eq!(output.value(), "Yehuda Katz");
+}
+
+#[test]
+fn rollback_lists_and_deletes() {
+    let mut timeline = Timeline::new();
+
+    let mut transaction = timeline.setup();
+    let fields = transaction.list(vec![
+        Field {
+            name: "first",
+            value: "Matt".to_string(),
+        },
+        Field {
+            name: "last",
+            value: "Hammer".to_string(),
+        },
+    ]);
+    let title = transaction.cell("Mr".to_string());
+
+    let mut output = timeline.list_output(fields);
+    let mut transaction = timeline.begin();
+    output.update(&mut transaction);
+    let rendered = output.value();
+
+    let mut transaction = timeline.stage();
+    transaction.push(
+        fields,
+        Field {
+            name: "middle",
+            value: "J".to_string(),
+        },
+    );
+    transaction.move_to(fields, &Key::string("last"), 0);
+    transaction.remove(fields, &Key::string("first"));
+    transaction.update(title, "Dr".to_string());
+    transaction.delete(title);
+    transaction.rollback();
+
+    assert!(timeline.contains(title));
+
+    let mut transaction = timeline.begin();
+    assert!(!output.update(&mut transaction));
+    assert_eq!(output.value(), rendered);
+
+    let mut title_output = timeline.output(title);
+    let mut transaction = timeline.begin();
+    title_output.update(&mut transaction);
+    assert_eq!(title_output.value(), "Mr");
+}
+
+#[test]
+fn rollback_keeps_dirty() {
+    let mut timeline = Timeline::new();
+
+    let mut transaction = timeline.setup();
+    let count = transaction.cell(1u32);
+    let external = transaction.external(|| 10u32);
+
+    let mut transaction = timeline.stage();
+    transaction.update(count, 2);
+    transaction.dirty(external);
+    transaction.rollback();
+
+    // the revision of the kept dirty is never handed out again
+    let dirtied = timeline.revision(external).unwrap();
+    timeline.update().update(count, 3);
+    assert!(timeline.revision(count).unwrap() > dirtied);
+}
@@ -0,0 +1,207 @@
+#![cfg(feature = "tracing")]
+
+use std::{
+    fmt::Debug,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc, Mutex,
+    },
+};
+
+use everafter::timeline::{DynId, EvaluationContext, Timeline};
+use tracing::{
+    field::{Field, Visit},
+    span, Event, Metadata, Subscriber,
+};
+
+/// A span or event, with its fields formatted with `Debug`, and the name of the span that it
+/// happened in.
+#[derive(Debug, Clone, Eq, PartialEq)]
+struct Record {
+    name: String,
+    parent: Option<String>,
+    fields: Vec<(String, String)>,
+}
+
+impl Record {
+    fn field(&self, name: &str) -> Option<&str> {
+        self.fields
+            .iter()
+            .find(|(field, _)| field == name)
+            .map(|(_, value)| &value[..])
+    }
+}
+
+impl Visit for Record {
+    fn record_debug(&mut self, field: &Field, value: &dyn Debug) {
+        self.fields
+            .push((field.name().to_string(), format!("{:?}", value)));
+    }
+}
+
+#[derive(Default, Clone)]
+struct Recorder {
+    next_id: Arc<AtomicU64>,
+    spans: Arc<Mutex<Vec<Record>>>,
+    events: Arc<Mutex<Vec<Record>>>,
+    /// The ids of the spans that are entered, innermost last.
+    entered: Arc<Mutex<Vec<u64>>>,
+}
+
+impl Recorder {
+    fn parent(&self, explicit: Option<&span::Id>, contextual: bool) -> Option<String> {
+        let id = match explicit {
+            Some(id) => Some(id.into_u64()),
+            None if contextual => self.entered.lock().unwrap().last().copied(),
+            None => None,
+        }?;
+
+        let spans = self.spans.lock().unwrap();
+        Some(spans[id as usize - 1].name.clone())
+    }
+}
+
+impl Subscriber for Recorder {
+    fn enabled(&self, _: &Metadata<'_>) -> bool {
+        true
+    }
+
+    fn new_span(&self, span: &span::Attributes<'_>) -> span::Id {
+        let mut record = Record {
+            name: span.metadata().name().to_string(),
+            parent: self.parent(span.parent(), span.is_contextual()),
+            fields: vec![],
+        };
+        span.record(&mut record);
+        self.spans.lock().unwrap().push(record);
+
+        span::Id::from_u64(self.next_id.fetch_add(1, Ordering::SeqCst) + 1)
+    }
+
+    fn record(&self, _: &span::Id, _: &span::Record<'_>) {}
+
+    fn record_follows_from(&self, _: &span::Id, _: &span::Id) {}
+
+    fn event(&self, event: &Event<'_>) {
+        let mut record = Record {
+            name: String::new(),
+            parent: self.parent(event.parent(), event.is_contextual()),
+            fields: vec![],
+        };
+        event.record(&mut record);
+        record.name = record.field("message").unwrap_or_default().to_string();
+        self.events.lock().unwrap().push(record);
+    }
+
+    fn enter(&self, id: &span::Id) {
+        self.entered.lock().unwrap().push(id.into_u64());
+    }
+
+    fn exit(&self, id: &span::Id) {
+        let mut entered = self.entered.lock().unwrap();
+
+        if let Some(index) = entered
+            .iter()
+            .rposition(|entered| *entered == id.into_u64())
+        {
+            entered.remove(index);
+        }
+    }
+}
+
+#[test]
+fn traces_transactions() {
+    let recorder = Recorder::default();
+
+    tracing::subscriber::with_default(recorder.clone(), || {
+        let mut timeline = Timeline::new();
+
+        let mut transaction = timeline.setup();
+        let count = transaction.cell(1u32);
+        let double = transaction.derived(move |ctx: &mut EvaluationContext| ctx.value(count) * 2);
+        transaction.commit();
+
+        let mut output = timeline.output(double);
+        output.update(&mut timeline.begin());
+        timeline.update().update(count, 2);
+
+        // every span stays entered until its transaction is done, so the spans and events that
+        // happen in the meantime are inside of it
+        let names: Vec<_> = recorder
+            .spans
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|span| (span.name.clone(), span.parent.clone()))
+            .collect();
+        assert_eq!(
+            names,
+            vec![
+                ("setup".to_string(), None),
+                ("render".to_string(), None),
+                ("compute_derived".to_string(), Some("render".to_string())),
+                ("read".to_string(), Some("compute_derived".to_string())),
+                ("read".to_string(), Some("render".to_string())),
+                ("update".to_string(), None),
+            ]
+        );
+
+        let spans = recorder.spans.lock().unwrap();
+        assert_eq!(spans[1].field("revision"), Some("«1»"));
+        assert_eq!(
+            spans[2].field("id"),
+            Some(&DynId::from(double).to_string()[..])
+        );
+        assert_eq!(spans[2].field("type_name"), Some("\"u32\""));
+
+        // cell reads are spans, not events
+        assert_eq!(
+            spans[3].field("id"),
+            Some(&DynId::from(count).to_string()[..])
+        );
+        assert_eq!(
+            spans[4].field("id"),
+            Some(&DynId::from(double).to_string()[..])
+        );
+
+        let events: Vec<_> = recorder
+            .events
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|event| {
+                (
+                    event.name.clone(),
+                    event.parent.clone(),
+                    event.field("id").map(str::to_string),
+                )
+            })
+            .collect();
+        assert_eq!(
+            events,
+            vec![
+                (
+                    "added".to_string(),
+                    Some("setup".to_string()),
+                    Some(DynId::from(count).to_string())
+                ),
+                (
+                    "added".to_string(),
+                    Some("setup".to_string()),
+                    Some(DynId::from(double).to_string())
+                ),
+                (
+                    "revision bump".to_string(),
+                    Some("update".to_string()),
+                    Some(DynId::from(count).to_string())
+                ),
+            ]
+        );
+
+        let bump = recorder.events.lock().unwrap().last().cloned().unwrap();
+        assert_eq!(bump.field("revision"), Some("«2»"));
+
+        // once every transaction is done, no span is entered anymore
+        assert!(recorder.entered.lock().unwrap().is_empty());
+    });
+}
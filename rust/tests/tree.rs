@@ -0,0 +1,83 @@
+use std::{cell::Cell, rc::Rc};
+
+use everafter::{
+    outputs::{Output, PrimitiveOutput, TreeChild, TreeOutput},
+    timeline::{EvaluationContext, Timeline},
+};
+
+#[test]
+fn tree_output() {
+    let mut timeline = Timeline::new();
+
+    // initialize inputs
+    let mut transaction = timeline.setup();
+    let title = transaction.cell("hello world".to_string());
+    let first = transaction.cell("Matt".to_string());
+    let last = transaction.cell("Hammer".to_string());
+
+    let computed = Rc::new(Cell::new(0));
+    let counter = computed.clone();
+
+    let full_name = transaction.derived(move |ctx: &mut EvaluationContext| {
+        counter.set(counter.get() + 1);
+        format!("{} {}", ctx.value(first), ctx.value(last))
+    });
+
+    // initialize outputs
+    let mut div = TreeOutput::new(vec![
+        TreeChild::node(TreeOutput::new(vec![TreeChild::leaf(
+            timeline.output(title),
+        )])),
+        TreeChild::node(TreeOutput::new(vec![TreeChild::leaf(
+            timeline.output(full_name),
+        )])),
+    ]);
+
+    let title_node = |div: &TreeOutput| {
+        div.leaf::<PrimitiveOutput<String>>(&[0, 0])
+            .expect("title node")
+            .value()
+    };
+
+    let name_node = |div: &TreeOutput| {
+        div.leaf::<PrimitiveOutput<String>>(&[1, 0])
+            .expect("name node")
+            .value()
+    };
+
+    // archive
+    let mut transaction = timeline.begin();
+    assert!(div.is_stale(&transaction));
+    assert!(div.update(&mut transaction));
+
+    assert_eq!(title_node(&div), "hello world");
+    assert_eq!(name_node(&div), "Matt Hammer");
+    assert_eq!(computed.get(), 1);
+
+    // edit
+    timeline.update().update(title, "goodbye world".to_string());
+
+    // archive
+    let mut transaction = timeline.begin();
+    assert!(div.update(&mut transaction));
+
+    assert_eq!(title_node(&div), "goodbye world");
+    assert_eq!(name_node(&div), "Matt Hammer");
+    assert_eq!(computed.get(), 1, "the paragraph subtree was fresh");
+
+    // edit
+    timeline.update().update(first, "Matthew".to_string());
+
+    // archive
+    let mut transaction = timeline.begin();
+    assert!(div.update(&mut transaction));
+
+    assert_eq!(title_node(&div), "goodbye world");
+    assert_eq!(name_node(&div), "Matthew Hammer");
+    assert_eq!(computed.get(), 2);
+
+    // archive without edits
+    let mut transaction = timeline.begin();
+    assert!(!div.is_stale(&transaction));
+    assert!(!div.update(&mut transaction));
+}